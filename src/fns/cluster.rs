@@ -0,0 +1,388 @@
+use crate::{
+    fns::{score::get_score_columns, util::round_value},
+    util::{
+        types::{
+            ClusterAnalysisData, ClusterData, ClusterSolutionData, Feedback, QualityCountData,
+            QuestionScoreData, ScoreColumns,
+        },
+        vars,
+    },
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+pub static KMEANS_METHOD: &str = "k_means";
+pub static KMEDOIDS_METHOD: &str = "k_medoids";
+
+#[derive(Clone, Copy)]
+enum ClusterMethod {
+    KMeans,
+    KMedoids,
+}
+
+struct Clustering {
+    assignments: Vec<usize>,
+    centres: Vec<Vec<f64>>,
+    medoids: Option<Vec<usize>>,
+    cost: f64,
+}
+
+/// Clusters respondents on their practice score vectors with k-means (Euclidean distance)
+/// and k-medoids (Manhattan distance) for every cluster count from two up to
+/// `MAX_CLUSTER_COUNT`. "Not applicable" answers keep their zero score, the neutral
+/// midpoint of the scale.
+pub fn calculate_clusters(feedbacks: &[Feedback]) -> Vec<ClusterAnalysisData> {
+    let columns: ScoreColumns = get_score_columns(feedbacks);
+
+    let points: Vec<Vec<f64>> = (0..columns.get_respondent_count())
+        .map(|respondent| {
+            columns
+                .get_respondent_scores(respondent)
+                .into_iter()
+                .map(|score| score as f64)
+                .collect()
+        })
+        .collect();
+    let questions: Vec<&'static str> = columns.questions.iter().map(|c| c.question).collect();
+    let ids: &[Option<usize>] = &columns.ids;
+    let quality: &[i8] = &columns.qualities;
+
+    let max_cluster_count = vars::MAX_CLUSTER_COUNT.min(points.len().saturating_sub(1));
+
+    [
+        (KMEANS_METHOD, ClusterMethod::KMeans),
+        (KMEDOIDS_METHOD, ClusterMethod::KMedoids),
+    ]
+    .into_iter()
+    .map(|(method_name, method)| {
+        let mut rng = StdRng::seed_from_u64(vars::RANDOM_SEED);
+
+        let solutions: Vec<ClusterSolutionData> = (2..=max_cluster_count)
+            .map(|cluster_count| {
+                let clustering = get_best_clustering(&points, cluster_count, method, &mut rng);
+                let silhouette = get_silhouette(&points, &clustering.assignments, method);
+
+                ClusterSolutionData {
+                    cluster_count,
+                    silhouette: round_value(silhouette),
+                    clusters: get_cluster_data(&clustering, &questions, ids, quality),
+                }
+            })
+            .collect();
+
+        let best_cluster_count = solutions
+            .iter()
+            .max_by(|a, b| a.silhouette.partial_cmp(&b.silhouette).unwrap())
+            .map(|s| s.cluster_count)
+            .unwrap_or(0);
+
+        ClusterAnalysisData {
+            method: method_name.to_owned(),
+            seed: vars::RANDOM_SEED,
+            best_cluster_count,
+            solutions,
+        }
+    })
+    .collect()
+}
+
+fn get_cluster_data(
+    clustering: &Clustering,
+    questions: &[&str],
+    ids: &[Option<usize>],
+    quality: &[i8],
+) -> Vec<ClusterData> {
+    let mut quality_levels: Vec<i8> = quality.to_vec();
+    quality_levels.sort();
+    quality_levels.dedup();
+
+    clustering
+        .centres
+        .iter()
+        .enumerate()
+        .map(|(cluster, centre)| {
+            let members: Vec<usize> = (0..ids.len())
+                .filter(|i| clustering.assignments[*i] == cluster)
+                .collect();
+            let size = members.len();
+
+            let quality_average =
+                members.iter().map(|i| quality[*i] as f64).sum::<f64>() / size.max(1) as f64;
+
+            let quality_distribution: Vec<QualityCountData> = quality_levels
+                .iter()
+                .map(|level| {
+                    let count = members.iter().filter(|i| quality[**i] == *level).count();
+                    QualityCountData {
+                        quality: *level,
+                        count,
+                        proportion: round_value(count as f64 / size.max(1) as f64),
+                    }
+                })
+                .collect();
+
+            ClusterData {
+                cluster: cluster + 1,
+                size,
+                medoid_id: clustering.medoids.as_ref().and_then(|m| ids[m[cluster]]),
+                centroid: questions
+                    .iter()
+                    .zip(centre.iter())
+                    .map(|(question, score)| QuestionScoreData {
+                        question: (*question).to_owned(),
+                        score: round_value(*score),
+                    })
+                    .collect(),
+                respondent_ids: members.iter().map(|i| ids[*i]).collect(),
+                quality_average: round_value(quality_average),
+                quality_distribution,
+            }
+        })
+        .collect()
+}
+
+fn get_distance(a: &[f64], b: &[f64], method: ClusterMethod) -> f64 {
+    match method {
+        ClusterMethod::KMeans => a
+            .iter()
+            .zip(b.iter())
+            .map(|(x, y)| f64::powf(x - y, 2f64))
+            .sum::<f64>()
+            .sqrt(),
+        ClusterMethod::KMedoids => a.iter().zip(b.iter()).map(|(x, y)| (x - y).abs()).sum(),
+    }
+}
+
+/// The quantity each method minimises: squared distance for k-means, distance for k-medoids.
+fn get_cost(a: &[f64], b: &[f64], method: ClusterMethod) -> f64 {
+    match method {
+        ClusterMethod::KMeans => f64::powf(get_distance(a, b, method), 2f64),
+        ClusterMethod::KMedoids => get_distance(a, b, method),
+    }
+}
+
+fn get_nearest_centre(point: &[f64], centres: &[Vec<f64>], method: ClusterMethod) -> (usize, f64) {
+    centres
+        .iter()
+        .enumerate()
+        .map(|(i, centre)| (i, get_cost(point, centre, method)))
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        .unwrap()
+}
+
+/// Runs `CLUSTER_RESTARTS` seeded fits and keeps the one with the lowest cost.
+fn get_best_clustering(
+    points: &[Vec<f64>],
+    cluster_count: usize,
+    method: ClusterMethod,
+    rng: &mut StdRng,
+) -> Clustering {
+    (0..vars::CLUSTER_RESTARTS)
+        .map(|_| {
+            let initial_centres = get_initial_centres(points, cluster_count, method, rng);
+            match method {
+                ClusterMethod::KMeans => fit_k_means(points, initial_centres),
+                ClusterMethod::KMedoids => fit_k_medoids(points, initial_centres),
+            }
+        })
+        .min_by(|a, b| a.cost.partial_cmp(&b.cost).unwrap())
+        .unwrap()
+}
+
+/// k-means++ seeding: each further centre is drawn with probability proportional to its cost
+/// to the nearest centre already chosen. Returns point indices.
+fn get_initial_centres(
+    points: &[Vec<f64>],
+    cluster_count: usize,
+    method: ClusterMethod,
+    rng: &mut StdRng,
+) -> Vec<usize> {
+    let mut centres: Vec<usize> = vec![rng.random_range(0..points.len())];
+
+    while centres.len() < cluster_count {
+        let costs: Vec<f64> = points
+            .iter()
+            .map(|point| {
+                centres
+                    .iter()
+                    .map(|c| get_cost(point, &points[*c], method))
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .collect();
+        let total_cost: f64 = costs.iter().sum();
+
+        let next = if total_cost > 0f64 {
+            let mut target = rng.random::<f64>() * total_cost;
+            costs
+                .iter()
+                .position(|cost| {
+                    target -= cost;
+                    target <= 0f64 && *cost > 0f64
+                })
+                .unwrap_or(points.len() - 1)
+        } else {
+            rng.random_range(0..points.len())
+        };
+
+        if !centres.contains(&next) {
+            centres.push(next);
+        } else if total_cost == 0f64 {
+            // Fewer distinct points than clusters; allow duplicates rather than looping forever.
+            centres.push(next);
+        }
+    }
+
+    centres
+}
+
+/// Index of the point farthest from its assigned centre, used to re-seed empty clusters.
+fn get_farthest_point(points: &[Vec<f64>], centres: &[Vec<f64>], method: ClusterMethod) -> usize {
+    (0..points.len())
+        .max_by(|a, b| {
+            let cost_a = get_nearest_centre(&points[*a], centres, method).1;
+            let cost_b = get_nearest_centre(&points[*b], centres, method).1;
+            cost_a.partial_cmp(&cost_b).unwrap()
+        })
+        .unwrap()
+}
+
+/// Lloyd's algorithm.
+fn fit_k_means(points: &[Vec<f64>], initial_centres: Vec<usize>) -> Clustering {
+    let method = ClusterMethod::KMeans;
+    let dimensions = points[0].len();
+    let mut centres: Vec<Vec<f64>> = initial_centres.iter().map(|i| points[*i].clone()).collect();
+    let mut assignments: Vec<usize> = vec![usize::MAX; points.len()];
+
+    for _ in 0..vars::MAX_SOLVER_ITERATIONS {
+        let updated: Vec<usize> = points
+            .iter()
+            .map(|point| get_nearest_centre(point, &centres, method).0)
+            .collect();
+
+        if updated == assignments {
+            break;
+        }
+        assignments = updated;
+
+        centres = (0..centres.len())
+            .map(|cluster| {
+                let members: Vec<&Vec<f64>> = points
+                    .iter()
+                    .zip(assignments.iter())
+                    .filter(|(_, a)| **a == cluster)
+                    .map(|(point, _)| point)
+                    .collect();
+
+                if members.is_empty() {
+                    return points[get_farthest_point(points, &centres, method)].clone();
+                }
+
+                (0..dimensions)
+                    .map(|d| members.iter().map(|m| m[d]).sum::<f64>() / members.len() as f64)
+                    .collect()
+            })
+            .collect();
+    }
+
+    let cost = points
+        .iter()
+        .zip(assignments.iter())
+        .map(|(point, a)| get_cost(point, &centres[*a], method))
+        .sum();
+
+    Clustering {
+        assignments,
+        centres,
+        medoids: None,
+        cost,
+    }
+}
+
+/// Alternating k-medoids: assign to the nearest medoid, then move each medoid to the
+/// member with the smallest total distance to the rest of its cluster.
+fn fit_k_medoids(points: &[Vec<f64>], initial_medoids: Vec<usize>) -> Clustering {
+    let method = ClusterMethod::KMedoids;
+    let mut medoids = initial_medoids;
+    let mut assignments: Vec<usize> = Vec::new();
+
+    for _ in 0..vars::MAX_SOLVER_ITERATIONS {
+        let centres: Vec<Vec<f64>> = medoids.iter().map(|m| points[*m].clone()).collect();
+        assignments = points
+            .iter()
+            .map(|point| get_nearest_centre(point, &centres, method).0)
+            .collect();
+
+        let updated: Vec<usize> = (0..medoids.len())
+            .map(|cluster| {
+                (0..points.len())
+                    .filter(|i| assignments[*i] == cluster)
+                    .map(|candidate| {
+                        let total_cost: f64 = (0..points.len())
+                            .filter(|i| assignments[*i] == cluster)
+                            .map(|i| get_cost(&points[candidate], &points[i], method))
+                            .sum();
+                        (candidate, total_cost)
+                    })
+                    .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                    .map(|(candidate, _)| candidate)
+                    .unwrap_or(medoids[cluster])
+            })
+            .collect();
+
+        if updated == medoids {
+            break;
+        }
+        medoids = updated;
+    }
+
+    let centres: Vec<Vec<f64>> = medoids.iter().map(|m| points[*m].clone()).collect();
+    let cost = points
+        .iter()
+        .zip(assignments.iter())
+        .map(|(point, a)| get_cost(point, &centres[*a], method))
+        .sum();
+
+    Clustering {
+        assignments,
+        centres,
+        medoids: Some(medoids),
+        cost,
+    }
+}
+
+/// Mean silhouette width; points in singleton clusters count as zero.
+/// See: Rousseeuw, P. J. (1987). Silhouettes: a graphical aid to the interpretation and
+/// validation of cluster analysis. Journal of Computational and Applied Mathematics, 20, 53-65.
+fn get_silhouette(points: &[Vec<f64>], assignments: &[usize], method: ClusterMethod) -> f64 {
+    let cluster_count = assignments.iter().max().map(|m| m + 1).unwrap_or(0);
+
+    let widths: Vec<f64> = (0..points.len())
+        .map(|i| {
+            let mut distance_sums = vec![0f64; cluster_count];
+            let mut counts = vec![0usize; cluster_count];
+
+            for j in (0..points.len()).filter(|j| *j != i) {
+                distance_sums[assignments[j]] += get_distance(&points[i], &points[j], method);
+                counts[assignments[j]] += 1;
+            }
+
+            let own = assignments[i];
+            if counts[own] == 0 {
+                return 0f64;
+            }
+
+            let a = distance_sums[own] / counts[own] as f64;
+            let b = (0..cluster_count)
+                .filter(|c| *c != own && counts[*c] > 0)
+                .map(|c| distance_sums[c] / counts[c] as f64)
+                .fold(f64::INFINITY, f64::min);
+
+            if !b.is_finite() || a.max(b) == 0f64 {
+                0f64
+            } else {
+                (b - a) / a.max(b)
+            }
+        })
+        .collect();
+
+    widths.iter().sum::<f64>() / widths.len().max(1) as f64
+}
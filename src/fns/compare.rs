@@ -0,0 +1,165 @@
+use crate::{
+    fns::{question::get_question_info, util::round_value},
+    util::{
+        types::{
+            ComparableResultsData, QuestionDataDiffs, QuestionResultComparisonData,
+            ResultComparisonData,
+        },
+        vars,
+    },
+};
+
+/// Compares two analysis runs question by question. A question counts as changed when its
+/// significance or quadrant flipped, when it is missing from one run, or when the change in
+/// rho, p-value or impact average exceeds `tolerance`.
+pub fn compare_results(
+    before: &ComparableResultsData,
+    after: &ComparableResultsData,
+    before_name: &str,
+    after_name: &str,
+    tolerance: Option<f64>,
+) -> ResultComparisonData {
+    let mut questions: Vec<String> = Vec::new();
+    for correlation in before.correlations.iter().chain(after.correlations.iter()) {
+        if !questions.contains(&correlation.question) {
+            questions.push(correlation.question.to_owned());
+        }
+    }
+
+    let before_diff_ranks: Vec<&str> = get_diff_ranks(&before.diffs);
+    let after_diff_ranks: Vec<&str> = get_diff_ranks(&after.diffs);
+
+    let mut comparisons: Vec<QuestionResultComparisonData> = Vec::new();
+    for question in questions.iter() {
+        let correlations = (
+            before.correlations.iter().find(|c| c.question == *question),
+            after.correlations.iter().find(|c| c.question == *question),
+        );
+        let diffs = (
+            before
+                .diffs
+                .iter()
+                .find(|d| d.question_correlation == *question),
+            after
+                .diffs
+                .iter()
+                .find(|d| d.question_correlation == *question),
+        );
+        let impacts = (
+            diffs.0.and_then(|d| {
+                before
+                    .impacts
+                    .iter()
+                    .find(|i| i.question == d.question_impact)
+            }),
+            diffs.1.and_then(|d| {
+                after
+                    .impacts
+                    .iter()
+                    .find(|i| i.question == d.question_impact)
+            }),
+        );
+
+        let rho = (correlations.0.map(|c| c.rho), correlations.1.map(|c| c.rho));
+        let p_value = (
+            correlations.0.map(|c| c.p_value),
+            correlations.1.map(|c| c.p_value),
+        );
+        let answer_count = (
+            correlations.0.map(|c| c.answer_count),
+            correlations.1.map(|c| c.answer_count),
+        );
+        let impact_average = (
+            impacts.0.map(|i| i.impact_average),
+            impacts.1.map(|i| i.impact_average),
+        );
+        let diff_rank = (
+            get_rank(&before_diff_ranks, question),
+            get_rank(&after_diff_ranks, question),
+        );
+        let quadrant = (diffs.0.map(|d| d.quadrant), diffs.1.map(|d| d.quadrant));
+
+        let rho_change = get_change(rho).map(round_value);
+        let p_value_change = get_change(p_value).map(round_value);
+        let impact_average_change =
+            get_change(impact_average).map(|c| round_value(c as f64) as f32);
+
+        let is_significance_changed = p_value.0.map(|p| p < vars::SIGNIFICANCE_LEVEL)
+            != p_value.1.map(|p| p < vars::SIGNIFICANCE_LEVEL);
+        let is_quadrant_changed = quadrant.0 != quadrant.1;
+        let is_missing = correlations.0.is_none() || correlations.1.is_none();
+        let is_beyond_tolerance = tolerance.is_some_and(|tolerance| {
+            [
+                rho_change,
+                p_value_change,
+                impact_average_change.map(|c| c as f64),
+            ]
+            .iter()
+            .flatten()
+            .any(|change| change.abs() > tolerance)
+        });
+
+        comparisons.push(QuestionResultComparisonData {
+            question: question.to_owned(),
+            question_info: get_question_info(question),
+            rho_before: rho.0,
+            rho_after: rho.1,
+            rho_change,
+            p_value_before: p_value.0,
+            p_value_after: p_value.1,
+            p_value_change,
+            answer_count_before: answer_count.0,
+            answer_count_after: answer_count.1,
+            answer_count_change: get_change((
+                answer_count.0.map(|n| n as i64),
+                answer_count.1.map(|n| n as i64),
+            )),
+            impact_average_before: impact_average.0,
+            impact_average_after: impact_average.1,
+            impact_average_change,
+            diff_rank_before: diff_rank.0,
+            diff_rank_after: diff_rank.1,
+            diff_rank_change: get_change((
+                diff_rank.0.map(|r| r as i64),
+                diff_rank.1.map(|r| r as i64),
+            )),
+            quadrant_before: quadrant.0,
+            quadrant_after: quadrant.1,
+            is_significance_changed,
+            is_quadrant_changed,
+            is_changed: is_significance_changed
+                || is_quadrant_changed
+                || is_missing
+                || is_beyond_tolerance,
+        });
+    }
+
+    ResultComparisonData {
+        before: before_name.to_owned(),
+        after: after_name.to_owned(),
+        tolerance,
+        changed_question_count: comparisons.iter().filter(|c| c.is_changed).count(),
+        questions: comparisons,
+    }
+}
+
+/// Correlation question names ordered by diff, largest first.
+fn get_diff_ranks(diffs: &[QuestionDataDiffs]) -> Vec<&str> {
+    let mut sorted: Vec<&QuestionDataDiffs> = diffs.iter().collect();
+    sorted.sort_by(|a, b| b.diff.partial_cmp(&a.diff).unwrap());
+    sorted
+        .iter()
+        .map(|d| d.question_correlation.as_str())
+        .collect()
+}
+
+fn get_rank(ranks: &[&str], question: &str) -> Option<usize> {
+    ranks.iter().position(|q| *q == question).map(|i| i + 1)
+}
+
+fn get_change<T: std::ops::Sub<Output = T> + Copy>(values: (Option<T>, Option<T>)) -> Option<T> {
+    match values {
+        (Some(before), Some(after)) => Some(after - before),
+        _ => None,
+    }
+}
@@ -1,8 +1,31 @@
-use crate::util::types::Feedback;
+use crate::{
+    fns::question::validate_question_fields,
+    util::{
+        types::{Feedback, FeedbackOrigin, InputFile},
+        vars,
+    },
+};
 use std::{error::Error, fs, path::Path};
 
-pub fn read_csv<P: AsRef<Path>>(filename: P) -> Result<Vec<Feedback>, Box<dyn Error>> {
-    let content = match get_csv_content(filename) {
+/// Reads and merges the input files in order, recording each response's wave and source row.
+pub fn read_csv(
+    inputs: &[InputFile],
+    group_columns: &[String],
+) -> Result<Vec<Feedback>, Box<dyn Error>> {
+    let mut feedbacks: Vec<Feedback> = Vec::new();
+
+    for input in inputs {
+        feedbacks.extend(read_csv_file(input, group_columns)?);
+    }
+
+    Ok(feedbacks)
+}
+
+fn read_csv_file(
+    input: &InputFile,
+    group_columns: &[String],
+) -> Result<Vec<Feedback>, Box<dyn Error>> {
+    let content = match get_csv_content(&input.path) {
         Ok(content) => content,
         Err(err) => {
             panic!("Failed to read CSV: {}", err);
@@ -10,23 +33,56 @@ pub fn read_csv<P: AsRef<Path>>(filename: P) -> Result<Vec<Feedback>, Box<dyn Er
     };
 
     let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let headers = reader.headers()?.clone();
+    let mut group_indices: Vec<(String, usize)> = Vec::new();
+
+    for column in group_columns {
+        match headers.iter().position(|header| header == column) {
+            Some(index) => group_indices.push((column.to_owned(), index)),
+            None => {
+                return Err(
+                    format!("Unknown grouping column in {}: {}", input.path, column).into(),
+                );
+            }
+        }
+    }
+
     let mut feedbacks: Vec<Feedback> = Vec::new();
 
-    for result in reader.deserialize() {
-        let record: Feedback = result?;
-        feedbacks.push(record);
+    for (row, result) in reader.records().enumerate() {
+        let record = result?;
+        let mut feedback: Feedback = record.deserialize(Some(&headers))?;
+
+        if row == 0 {
+            validate_question_fields(&feedback)?;
+        }
+
+        feedback.origin = FeedbackOrigin {
+            wave: input.wave.to_owned(),
+            file: input.path.to_owned(),
+            row: row + 1,
+        };
+
+        for (column, index) in group_indices.iter() {
+            let value = record.get(*index).unwrap_or_default().trim();
+            feedback.groups.insert(column.to_owned(), value.to_owned());
+        }
+
+        feedbacks.push(feedback);
     }
 
     Ok(feedbacks)
 }
 
+#[allow(clippy::needless_return)]
 fn get_csv_content<P: AsRef<Path>>(filename: P) -> Result<String, Box<dyn Error>> {
     let content: String = fs::read_to_string(filename)?;
     let reduced_content = remove_boilerplate_content(content);
     return Ok(reduced_content);
 }
 
+#[allow(clippy::needless_return)]
 fn remove_boilerplate_content(content: String) -> String {
-    let replaced = content.replace("In general, how impactful on quality are the following development practices in your opinion?\n1 means not impactful at all and 5 means very impactful.  ", "");
+    let replaced = content.replace(vars::IMPACT_HEADER_PREFIX, "");
     return replaced;
 }
@@ -0,0 +1,54 @@
+use crate::{
+    fns::{question::get_question_info, ranking::get_ranking, score::get_score_columns},
+    util::{
+        types::{Feedback, RespondentScoreData, ScoreColumns, TieMethod},
+        vars,
+    },
+};
+
+/// Builds the tidy long-format dataset behind the correlations: one row per respondent and
+/// practice question, with the score and quality ranked among each question's applicable
+/// answers with `tie_method`. The correlations always use the average ranks of the score
+/// columns.
+pub fn get_respondent_scores(
+    feedbacks: &[Feedback],
+    tie_method: TieMethod,
+) -> Vec<RespondentScoreData> {
+    let columns: ScoreColumns = get_score_columns(feedbacks);
+    let mut rows: Vec<RespondentScoreData> = Vec::new();
+
+    for column in columns.questions.iter() {
+        let (scores, qualities) = columns.get_applicable_scores(column);
+        let mut score_ranks = get_ranking(&scores, tie_method).ranks.into_iter();
+        let mut quality_ranks = get_ranking(&qualities, tie_method).ranks.into_iter();
+
+        for (j, feedback) in feedbacks.iter().enumerate() {
+            let score = column.scores[j];
+
+            rows.push(RespondentScoreData {
+                respondent_id: feedback.id,
+                wave: feedback.origin.wave.to_owned(),
+                question: column.question.to_owned(),
+                question_info: get_question_info(column.question),
+                answer: feedback
+                    .get_answer(column.question)
+                    .unwrap_or_default()
+                    .to_owned(),
+                score,
+                rank: if score != vars::WEIGHTING_NOT_APPLICABLE {
+                    score_ranks.next()
+                } else {
+                    None
+                },
+                quality: feedback.project_quality_estimate,
+                quality_rank: if score != vars::WEIGHTING_NOT_APPLICABLE {
+                    quality_ranks.next()
+                } else {
+                    None
+                },
+            });
+        }
+    }
+
+    rows
+}
@@ -0,0 +1,563 @@
+use crate::{
+    fns::{
+        question::get_question_info,
+        rank::{adjust_p_values, get_pearson_correlation, get_spearman_correlation},
+        score::get_score_columns,
+        stats::get_correlation_confidence_interval,
+        util::round_value,
+    },
+    util::{
+        types::{
+            FactorAnalysisData, FactorLoadingData, FactorScoreData, FactorSolutionData, Feedback,
+            QuestionCorrelationData, ScoreColumns, ScreeData,
+        },
+        vars,
+    },
+};
+use nalgebra::{DMatrix, DVector, SymmetricEigen};
+
+pub static PCA_METHOD: &str = "pca";
+pub static VARIMAX_METHOD: &str = "paf_varimax";
+pub static OBLIMIN_METHOD: &str = "paf_oblimin";
+
+static MIN_COMMUNALITY: f64 = 0.005;
+static MAX_COMMUNALITY: f64 = 0.995;
+static ROTATION_TOLERANCE: f64 = 1e-5;
+static MAX_ROTATION_ITERATIONS: usize = 1000;
+static MAX_STEP_HALVINGS: usize = 10;
+static PSEUDO_INVERSE_EPSILON: f64 = 1e-10;
+
+enum Rotation {
+    Varimax,
+    Oblimin,
+}
+
+/// Principal component analysis and principal axis factoring of the practice score matrix.
+///
+/// The correlation matrix uses pairwise complete answers. For scores the answers are
+/// standardised and "not applicable" answers are set to the question mean (zero).
+/// The number of factors follows the Kaiser criterion on the correlation matrix. Without at
+/// least two questions with three varying answers there are no factors.
+pub fn calculate_factor_analysis(feedbacks: &[Feedback]) -> FactorAnalysisData {
+    let score_columns: ScoreColumns = get_score_columns(feedbacks);
+
+    let ids: &[Option<usize>] = &score_columns.ids;
+    let quality: Vec<f64> = score_columns
+        .qualities
+        .iter()
+        .map(|quality| *quality as f64)
+        .collect();
+
+    let mut questions: Vec<String> = Vec::new();
+    let mut columns: Vec<Vec<Option<f64>>> = Vec::new();
+
+    for score_column in score_columns.questions.iter() {
+        let column: Vec<Option<f64>> = score_column
+            .scores
+            .iter()
+            .map(|score| match *score {
+                score if score == vars::WEIGHTING_NOT_APPLICABLE => None,
+                score => Some(score as f64),
+            })
+            .collect();
+
+        let applicable: Vec<f64> = column.iter().flatten().copied().collect();
+        let is_constant = applicable.windows(2).all(|w| w[0] == w[1]);
+
+        if applicable.len() >= 3 && !is_constant {
+            questions.push(score_column.question.to_owned());
+            columns.push(column);
+        }
+    }
+
+    // Too few varying questions to correlate, e.g. with fewer than three respondents
+    if questions.len() < 2 {
+        return FactorAnalysisData {
+            questions,
+            respondent_count: score_columns.get_respondent_count(),
+            scree: Vec::new(),
+            solutions: Vec::new(),
+        };
+    }
+
+    let correlations = get_pairwise_correlations(&columns);
+    let standardised = get_standardised_matrix(&columns, score_columns.get_respondent_count());
+    let (eigenvalues, eigenvectors) = get_sorted_eigen(correlations.clone());
+    let question_count = questions.len() as f64;
+
+    let mut cumulative_explained_variance = 0f64;
+    let scree: Vec<ScreeData> = eigenvalues
+        .iter()
+        .enumerate()
+        .map(|(i, eigenvalue)| {
+            let explained_variance = eigenvalue / question_count;
+            cumulative_explained_variance += explained_variance;
+
+            ScreeData {
+                component: i + 1,
+                eigenvalue: round_value(*eigenvalue),
+                explained_variance: round_value(explained_variance),
+                cumulative_explained_variance: round_value(cumulative_explained_variance),
+            }
+        })
+        .collect();
+
+    let factor_count = eigenvalues
+        .iter()
+        .filter(|e| **e > vars::FACTOR_EIGENVALUE_THRESHOLD)
+        .count()
+        .max(1)
+        .min(questions.len());
+
+    let component_loadings = DMatrix::from_fn(questions.len(), factor_count, |i, j| {
+        eigenvectors[(i, j)] * eigenvalues[j].max(0f64).sqrt()
+    });
+    let axis_loadings = get_principal_axis_loadings(&correlations, factor_count);
+
+    let varimax_loadings = denormalise_rows(
+        &rotate_orthogonal(&normalise_rows(&axis_loadings), &Rotation::Varimax),
+        &axis_loadings,
+    );
+    let (oblimin_loadings, oblimin_correlations) =
+        rotate_oblique(&axis_loadings, &Rotation::Oblimin);
+
+    let solutions: Vec<FactorSolutionData> = [
+        (PCA_METHOD, component_loadings, None),
+        (VARIMAX_METHOD, varimax_loadings, None),
+        (OBLIMIN_METHOD, oblimin_loadings, Some(oblimin_correlations)),
+    ]
+    .into_iter()
+    .map(|(method, loadings, factor_correlations)| {
+        let (loadings, factor_correlations) = align_factors(loadings, factor_correlations);
+
+        get_solution_data(
+            method,
+            &questions,
+            &loadings,
+            factor_correlations.as_ref(),
+            &correlations,
+            &standardised,
+            ids,
+            &quality,
+        )
+    })
+    .collect();
+
+    FactorAnalysisData {
+        questions,
+        respondent_count: score_columns.get_respondent_count(),
+        scree,
+        solutions,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn get_solution_data(
+    method: &str,
+    questions: &[String],
+    loadings: &DMatrix<f64>,
+    factor_correlations: Option<&DMatrix<f64>>,
+    correlations: &DMatrix<f64>,
+    standardised: &DMatrix<f64>,
+    ids: &[Option<usize>],
+    quality: &[f64],
+) -> FactorSolutionData {
+    let factor_count = loadings.ncols();
+    let identity = DMatrix::identity(factor_count, factor_count);
+    let factor_correlations_or_identity = factor_correlations.unwrap_or(&identity);
+    let communalities =
+        (loadings * factor_correlations_or_identity * loadings.transpose()).diagonal();
+
+    let loading_data: Vec<FactorLoadingData> = questions
+        .iter()
+        .enumerate()
+        .map(|(i, question)| FactorLoadingData {
+            question: question.to_owned(),
+            question_info: get_question_info(question),
+            loadings: loadings.row(i).iter().map(|l| round_value(*l)).collect(),
+            communality: round_value(communalities[i]),
+        })
+        .collect();
+
+    let explained_variance: Vec<f64> = loadings
+        .column_iter()
+        .map(|column| round_value(column.norm_squared() / questions.len() as f64))
+        .collect();
+
+    // Regression (Thurstone) scores: Z R^-1 S, where S = loadings * factor correlations.
+    let weights = correlations
+        .clone()
+        .pseudo_inverse(PSEUDO_INVERSE_EPSILON)
+        .expect("Failed to invert the correlation matrix")
+        * loadings
+        * factor_correlations_or_identity;
+    let factor_scores = standardised * weights;
+
+    let scores: Vec<FactorScoreData> = ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| FactorScoreData {
+            id: *id,
+            scores: factor_scores
+                .row(i)
+                .iter()
+                .map(|s| round_value(*s))
+                .collect(),
+        })
+        .collect();
+
+    let mut quality_correlations: Vec<QuestionCorrelationData> = Vec::new();
+
+    if ids.len() >= 3 {
+        for factor in 0..factor_count {
+            let factor_column: Vec<f64> = factor_scores.column(factor).iter().copied().collect();
+            let (rho, p_value) = get_spearman_correlation(&factor_column, quality);
+            let (rho_ci_lower, rho_ci_upper) = get_correlation_confidence_interval(rho, ids.len());
+
+            quality_correlations.push(QuestionCorrelationData {
+                question: format!("{}_factor_{}", method, factor + 1),
+                question_info: None,
+                rho: round_value(rho),
+                rho_ci_lower: round_value(rho_ci_lower),
+                rho_ci_upper: round_value(rho_ci_upper),
+                p_value: round_value(p_value),
+                p_value_adjusted: round_value(p_value),
+                answer_count: ids.len(),
+                imputed_answer_count: None,
+            });
+        }
+    }
+    adjust_p_values(&mut quality_correlations);
+
+    FactorSolutionData {
+        method: method.to_owned(),
+        factor_count,
+        loadings: loading_data,
+        explained_variance,
+        factor_correlations: factor_correlations.map(|c| {
+            c.row_iter()
+                .map(|row| row.iter().map(|r| round_value(*r)).collect())
+                .collect()
+        }),
+        scores,
+        quality_correlations,
+    }
+}
+
+fn get_pairwise_correlations(columns: &[Vec<Option<f64>>]) -> DMatrix<f64> {
+    DMatrix::from_fn(columns.len(), columns.len(), |i, j| {
+        if i == j {
+            return 1f64;
+        }
+
+        let (x, y): (Vec<f64>, Vec<f64>) = columns[i]
+            .iter()
+            .zip(columns[j].iter())
+            .filter_map(|(a, b)| Some(((*a)?, (*b)?)))
+            .unzip();
+
+        if x.len() < 3 {
+            return 0f64;
+        }
+
+        let correlation = get_pearson_correlation(&x, &y);
+        if correlation.is_finite() {
+            correlation
+        } else {
+            0f64
+        }
+    })
+}
+
+fn get_standardised_matrix(columns: &[Vec<Option<f64>>], respondent_count: usize) -> DMatrix<f64> {
+    let mut standardised: DMatrix<f64> = DMatrix::zeros(respondent_count, columns.len());
+
+    for (j, column) in columns.iter().enumerate() {
+        let applicable: Vec<f64> = column.iter().flatten().copied().collect();
+        let mean = applicable.iter().sum::<f64>() / applicable.len() as f64;
+        let variance = applicable
+            .iter()
+            .map(|v| f64::powf(v - mean, 2f64))
+            .sum::<f64>()
+            / (applicable.len() - 1) as f64;
+
+        for (i, value) in column.iter().enumerate() {
+            if let Some(v) = value {
+                standardised[(i, j)] = (v - mean) / variance.sqrt();
+            }
+        }
+    }
+
+    standardised
+}
+
+/// Eigen-decomposition with the eigenvalues in descending order.
+fn get_sorted_eigen(matrix: DMatrix<f64>) -> (Vec<f64>, DMatrix<f64>) {
+    let eigen = SymmetricEigen::new(matrix);
+    let mut order: Vec<usize> = (0..eigen.eigenvalues.len()).collect();
+    order.sort_by(|a, b| {
+        eigen.eigenvalues[*b]
+            .partial_cmp(&eigen.eigenvalues[*a])
+            .unwrap()
+    });
+
+    let eigenvalues: Vec<f64> = order.iter().map(|i| eigen.eigenvalues[*i]).collect();
+    let eigenvectors = DMatrix::from_fn(eigen.eigenvectors.nrows(), order.len(), |i, j| {
+        eigen.eigenvectors[(i, order[j])]
+    });
+
+    (eigenvalues, eigenvectors)
+}
+
+/// Iterated principal axis factoring starting from squared multiple correlations.
+fn get_principal_axis_loadings(correlations: &DMatrix<f64>, factor_count: usize) -> DMatrix<f64> {
+    let inverse = correlations
+        .clone()
+        .pseudo_inverse(PSEUDO_INVERSE_EPSILON)
+        .expect("Failed to invert the correlation matrix");
+
+    let mut communalities: DVector<f64> = inverse
+        .diagonal()
+        .map(|d| (1f64 - 1f64 / d).clamp(MIN_COMMUNALITY, MAX_COMMUNALITY));
+    let mut loadings: DMatrix<f64> = DMatrix::zeros(correlations.nrows(), factor_count);
+
+    for _ in 0..vars::MAX_SOLVER_ITERATIONS {
+        let mut reduced = correlations.clone();
+        reduced.set_diagonal(&communalities);
+
+        let (eigenvalues, eigenvectors) = get_sorted_eigen(reduced);
+        loadings = DMatrix::from_fn(correlations.nrows(), factor_count, |i, j| {
+            eigenvectors[(i, j)] * eigenvalues[j].max(0f64).sqrt()
+        });
+
+        let updated: DVector<f64> = DVector::from_fn(correlations.nrows(), |i, _| {
+            loadings.row(i).norm_squared().min(MAX_COMMUNALITY)
+        });
+        let change = (&updated - &communalities).amax();
+        communalities = updated;
+
+        if change < vars::SOLVER_TOLERANCE {
+            break;
+        }
+    }
+
+    loadings
+}
+
+/// Kaiser normalisation: scales each row of loadings to unit length before rotation.
+fn normalise_rows(loadings: &DMatrix<f64>) -> DMatrix<f64> {
+    let mut normalised = loadings.clone();
+    for mut row in normalised.row_iter_mut() {
+        let norm = row.norm();
+        if norm > 0f64 {
+            row /= norm;
+        }
+    }
+    normalised
+}
+
+fn denormalise_rows(normalised: &DMatrix<f64>, original: &DMatrix<f64>) -> DMatrix<f64> {
+    let mut loadings = normalised.clone();
+    for (mut row, original_row) in loadings.row_iter_mut().zip(original.row_iter()) {
+        row *= original_row.norm();
+    }
+    loadings
+}
+
+/// Returns the rotation criterion value and its gradient with respect to the loadings.
+fn get_criterion(loadings: &DMatrix<f64>, rotation: &Rotation) -> (f64, DMatrix<f64>) {
+    let squared = loadings.map(|l| l * l);
+
+    match rotation {
+        Rotation::Varimax => {
+            let mut centred = squared.clone();
+            for mut column in centred.column_iter_mut() {
+                let mean = column.mean();
+                column.add_scalar_mut(-mean);
+            }
+            let value = -centred.norm_squared() / 4f64;
+            (value, -loadings.component_mul(&centred))
+        }
+        Rotation::Oblimin => {
+            let factor_count = loadings.ncols();
+            let off_diagonal = DMatrix::from_element(factor_count, factor_count, 1f64)
+                - DMatrix::identity(factor_count, factor_count);
+            let cross = &squared * off_diagonal;
+            let value = squared.component_mul(&cross).sum() / 4f64;
+            (value, loadings.component_mul(&cross))
+        }
+    }
+}
+
+/// Orthogonal gradient projection rotation.
+/// See: Jennrich, R. I. (2001). A simple general procedure for orthogonal rotation.
+/// Psychometrika, 66(2), 289-306.
+fn rotate_orthogonal(loadings: &DMatrix<f64>, rotation: &Rotation) -> DMatrix<f64> {
+    let factor_count = loadings.ncols();
+    let mut transformation: DMatrix<f64> = DMatrix::identity(factor_count, factor_count);
+    let mut rotated = loadings.clone();
+    let (mut value, gradient_q) = get_criterion(&rotated, rotation);
+    let mut gradient = loadings.transpose() * gradient_q;
+    let mut step_size = 1f64;
+
+    for _ in 0..MAX_ROTATION_ITERATIONS {
+        let m = transformation.transpose() * &gradient;
+        let symmetric = (&m + m.transpose()) / 2f64;
+        let projected = &gradient - &transformation * symmetric;
+        let projected_norm = projected.norm();
+
+        if projected_norm < ROTATION_TOLERANCE {
+            break;
+        }
+
+        step_size *= 2f64;
+        let mut candidate = (
+            transformation.clone(),
+            rotated.clone(),
+            value,
+            gradient.clone(),
+        );
+
+        for _ in 0..=MAX_STEP_HALVINGS {
+            let svd = (&transformation - &projected * step_size).svd(true, true);
+            let candidate_transformation = svd.u.unwrap() * svd.v_t.unwrap();
+            let candidate_rotated = loadings * &candidate_transformation;
+            let (candidate_value, candidate_gradient_q) =
+                get_criterion(&candidate_rotated, rotation);
+
+            candidate = (
+                candidate_transformation,
+                candidate_rotated,
+                candidate_value,
+                loadings.transpose() * candidate_gradient_q,
+            );
+
+            if value - candidate_value > 0.5 * projected_norm.powi(2) * step_size {
+                break;
+            }
+            step_size /= 2f64;
+        }
+
+        (transformation, rotated, value, gradient) = candidate;
+    }
+
+    rotated
+}
+
+/// Oblique gradient projection rotation. Returns the pattern loadings and factor correlations.
+/// See: Jennrich, R. I. (2002). A simple general method for oblique rotation.
+/// Psychometrika, 67(1), 7-19.
+fn rotate_oblique(loadings: &DMatrix<f64>, rotation: &Rotation) -> (DMatrix<f64>, DMatrix<f64>) {
+    let factor_count = loadings.ncols();
+    let get_rotated = |transformation: &DMatrix<f64>| -> Option<DMatrix<f64>> {
+        Some(loadings * transformation.clone().try_inverse()?.transpose())
+    };
+    let get_gradient = |rotated: &DMatrix<f64>,
+                        gradient_q: &DMatrix<f64>,
+                        transformation: &DMatrix<f64>|
+     -> DMatrix<f64> {
+        -(rotated.transpose() * gradient_q * transformation.clone().try_inverse().unwrap())
+            .transpose()
+    };
+
+    let mut transformation: DMatrix<f64> = DMatrix::identity(factor_count, factor_count);
+    let mut rotated = loadings.clone();
+    let (mut value, gradient_q) = get_criterion(&rotated, rotation);
+    let mut gradient = get_gradient(&rotated, &gradient_q, &transformation);
+    let mut step_size = 1f64;
+
+    for _ in 0..MAX_ROTATION_ITERATIONS {
+        let column_sums = DVector::from_fn(factor_count, |j, _| {
+            transformation.column(j).dot(&gradient.column(j))
+        });
+        let projected = &gradient - &transformation * DMatrix::from_diagonal(&column_sums);
+        let projected_norm = projected.norm();
+
+        if projected_norm < ROTATION_TOLERANCE {
+            break;
+        }
+
+        step_size *= 2f64;
+        let mut candidate = None;
+
+        for _ in 0..=MAX_STEP_HALVINGS {
+            let mut candidate_transformation = &transformation - &projected * step_size;
+            for mut column in candidate_transformation.column_iter_mut() {
+                let norm = column.norm();
+                column /= norm;
+            }
+
+            if let Some(candidate_rotated) = get_rotated(&candidate_transformation) {
+                let (candidate_value, candidate_gradient_q) =
+                    get_criterion(&candidate_rotated, rotation);
+                let is_improvement =
+                    value - candidate_value > 0.5 * projected_norm.powi(2) * step_size;
+
+                candidate = Some((
+                    candidate_transformation,
+                    candidate_rotated,
+                    candidate_value,
+                    candidate_gradient_q,
+                ));
+
+                if is_improvement {
+                    break;
+                }
+            }
+            step_size /= 2f64;
+        }
+
+        let Some((candidate_transformation, candidate_rotated, candidate_value, gradient_q)) =
+            candidate
+        else {
+            break;
+        };
+
+        gradient = get_gradient(&candidate_rotated, &gradient_q, &candidate_transformation);
+        transformation = candidate_transformation;
+        rotated = candidate_rotated;
+        value = candidate_value;
+    }
+
+    let factor_correlations = transformation.transpose() * &transformation;
+    (rotated, factor_correlations)
+}
+
+/// Orders factors by their sum of squared loadings and flips signs so that
+/// each factor's loadings sum to a positive value.
+fn align_factors(
+    loadings: DMatrix<f64>,
+    factor_correlations: Option<DMatrix<f64>>,
+) -> (DMatrix<f64>, Option<DMatrix<f64>>) {
+    let factor_count = loadings.ncols();
+    let mut order: Vec<usize> = (0..factor_count).collect();
+    order.sort_by(|a, b| {
+        loadings
+            .column(*b)
+            .norm_squared()
+            .partial_cmp(&loadings.column(*a).norm_squared())
+            .unwrap()
+    });
+
+    let signs: Vec<f64> = order
+        .iter()
+        .map(|j| {
+            if loadings.column(*j).sum() < 0f64 {
+                -1f64
+            } else {
+                1f64
+            }
+        })
+        .collect();
+
+    let aligned_loadings = DMatrix::from_fn(loadings.nrows(), factor_count, |i, j| {
+        loadings[(i, order[j])] * signs[j]
+    });
+    let aligned_correlations = factor_correlations.map(|c| {
+        DMatrix::from_fn(factor_count, factor_count, |i, j| {
+            c[(order[i], order[j])] * signs[i] * signs[j]
+        })
+    });
+
+    (aligned_loadings, aligned_correlations)
+}
@@ -0,0 +1,97 @@
+use crate::{
+    fns::{question::get_question_info, score::get_score_columns, util::round_value},
+    util::{
+        types::{
+            AnswerFrequencyData, CrosstabCellData, Feedback, QuestionFrequencyData, ScoreColumns,
+            ScoreFrequencyData,
+        },
+        vars,
+    },
+};
+
+/// Counts each answer text and score level per practice question, including the answers
+/// scored as not applicable, and crosstabs the score with the quality estimate.
+pub fn calculate_frequencies(feedbacks: &[Feedback]) -> Vec<QuestionFrequencyData> {
+    let columns: ScoreColumns = get_score_columns(feedbacks);
+    let respondent_count = columns.get_respondent_count();
+
+    let mut qualities: Vec<i8> = columns.qualities.clone();
+    qualities.sort();
+    qualities.dedup();
+
+    let score_levels: Vec<i8> = (vars::WEIGHTING_FULL_NEG..=vars::WEIGHTING_FULL)
+        .rev()
+        .collect();
+    let get_percentage = |count: usize, total: usize| {
+        if total == 0 {
+            0f64
+        } else {
+            round_value(count as f64 / total as f64 * 100f64)
+        }
+    };
+
+    columns
+        .questions
+        .iter()
+        .map(|column| {
+            let question = column.question;
+            let mut answers: Vec<AnswerFrequencyData> = Vec::new();
+            for (feedback, score) in feedbacks.iter().zip(&column.scores) {
+                let answer = feedback.get_answer(question).unwrap_or_default();
+                match answers.iter_mut().find(|a| a.answer == answer) {
+                    Some(frequency) => frequency.count += 1,
+                    None => answers.push(AnswerFrequencyData {
+                        answer: answer.to_owned(),
+                        score: *score,
+                        count: 1,
+                        percentage: 0f64,
+                    }),
+                }
+            }
+            for frequency in answers.iter_mut() {
+                frequency.percentage = get_percentage(frequency.count, respondent_count);
+            }
+            answers.sort_by(|a, b| b.score.cmp(&a.score).then(b.count.cmp(&a.count)));
+
+            let score_counts: Vec<usize> = score_levels
+                .iter()
+                .map(|level| column.scores.iter().filter(|s| *s == level).count())
+                .collect();
+
+            let mut crosstab: Vec<CrosstabCellData> = Vec::new();
+            for (level, score_count) in score_levels.iter().zip(&score_counts) {
+                for quality in qualities.iter() {
+                    let count = column
+                        .scores
+                        .iter()
+                        .zip(&columns.qualities)
+                        .filter(|(s, q)| *s == level && *q == quality)
+                        .count();
+                    crosstab.push(CrosstabCellData {
+                        score: *level,
+                        quality: *quality,
+                        count,
+                        percentage: get_percentage(count, *score_count),
+                    });
+                }
+            }
+
+            QuestionFrequencyData {
+                question: question.to_owned(),
+                question_info: get_question_info(question),
+                respondent_count,
+                answers,
+                scores: score_levels
+                    .iter()
+                    .zip(score_counts)
+                    .map(|(level, count)| ScoreFrequencyData {
+                        score: *level,
+                        count,
+                        percentage: get_percentage(count, respondent_count),
+                    })
+                    .collect(),
+                crosstab,
+            }
+        })
+        .collect()
+}
@@ -1,32 +1,451 @@
-use crate::util::types::{QuestionCorrelationData, QuestionDataDiffs, QuestionImpactData};
-use serde::Serialize;
+use crate::util::args::{ExportFormat, OutputFormat};
+use crate::util::types::{
+    ClusterAnalysisData, ComparableResultsData, FactorAnalysisData, ImpactAverageData,
+    PowerAnalysisData, QuestionCorrelationData, QuestionDataDiffs, QuestionFrequencyData,
+    QuestionImpactData, QuestionInfluenceData, QuestionInfo, QuestionMissingData,
+    QuestionOptimalScalingData, QuestionOrdinalRegressionData, RespondentScoreData,
+    ResultComparisonData, ResultsData, SimulatedSurvey, SkippedQuestionData, SubgroupAnalysisData,
+    WaveComparisonData, WeightingSensitivityData,
+};
+use csv::WriterBuilder;
+use parquet::{
+    data_type::{ByteArray, ByteArrayType, DataType, DoubleType, Int32Type, Int64Type},
+    errors::ParquetError,
+    file::writer::{SerializedColumnWriter, SerializedFileWriter},
+    schema::parser::parse_message_type,
+};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
 use std::{
-    fs::{File, create_dir_all},
-    io::{BufWriter, Write},
+    error::Error,
+    fs::{self, File, create_dir_all},
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+    sync::Arc,
 };
 
 static OUT_DIR: &str = "out";
+static RESPONDENT_SCORES_SCHEMA: &str = "message respondent_scores {
+    OPTIONAL INT64 respondent_id;
+    OPTIONAL BYTE_ARRAY wave (UTF8);
+    OPTIONAL BYTE_ARRAY question (UTF8);
+    OPTIONAL BYTE_ARRAY question_id (UTF8);
+    OPTIONAL BYTE_ARRAY question_text (UTF8);
+    OPTIONAL BYTE_ARRAY question_label (UTF8);
+    OPTIONAL BYTE_ARRAY question_category (UTF8);
+    OPTIONAL BYTE_ARRAY answer (UTF8);
+    OPTIONAL INT32 score;
+    OPTIONAL DOUBLE rank;
+    OPTIONAL INT32 quality;
+    OPTIONAL DOUBLE quality_rank;
+}";
+
+pub fn write_correlation_data(data: &[QuestionCorrelationData], format: OutputFormat) {
+    write_rows("correlation_data", data, format);
+}
+
+pub fn write_impact_data(data: &[QuestionImpactData], format: OutputFormat) {
+    write_rows("impact_data", data, format);
+}
+
+pub fn write_diffs_data(data: &[QuestionDataDiffs], format: OutputFormat) {
+    write_rows("diffs", data, format);
+}
+
+pub fn write_impact_average(average: f64, format: OutputFormat) {
+    write_data(
+        "impact_average",
+        &ImpactAverageData {
+            impact_average: average,
+        },
+        format,
+    );
+}
+
+pub fn write_frequency_data(data: &[QuestionFrequencyData], format: OutputFormat) {
+    write_rows("frequency_data", data, format);
+}
+
+pub fn write_influence_data(data: &[QuestionInfluenceData], format: OutputFormat) {
+    write_rows("influence_data", data, format);
+}
+
+pub fn write_ordinal_regression_data(data: &[QuestionOrdinalRegressionData], format: OutputFormat) {
+    write_rows("ordinal_regression_data", data, format);
+}
+
+pub fn write_missing_data(data: &[QuestionMissingData], format: OutputFormat) {
+    write_rows("missing_data", data, format);
+}
+
+pub fn write_optimal_scaling_data(data: &[QuestionOptimalScalingData], format: OutputFormat) {
+    write_rows("optimal_scaling_data", data, format);
+}
+
+pub fn write_factor_analysis_data(data: &FactorAnalysisData, format: OutputFormat) {
+    write_data("factor_analysis_data", data, format);
+}
 
-pub fn write_correlation_data(data: &Vec<QuestionCorrelationData>) {
-    write_data(&format!("{}/correlation_data.json", OUT_DIR), data);
+pub fn write_skipped_question_data(data: &[SkippedQuestionData], format: OutputFormat) {
+    write_rows("skipped_question_data", data, format);
 }
 
-pub fn write_impact_data(data: &Vec<QuestionImpactData>) {
-    write_data(&format!("{}/impact_data.json", OUT_DIR), data);
+pub fn write_cluster_data(data: &[ClusterAnalysisData], format: OutputFormat) {
+    write_rows("cluster_data", data, format);
 }
 
-pub fn write_diffs_data(data: &Vec<QuestionDataDiffs>) {
-    write_data(&format!("{}/diffs.json", OUT_DIR), data);
+pub fn write_subgroup_data(data: &[SubgroupAnalysisData], format: OutputFormat) {
+    write_rows("subgroup_data", data, format);
 }
 
-pub fn write_impact_average(average: f64) {
-    write_data(&format!("{}/impact_average.json", OUT_DIR), &vec![average]);
+pub fn write_wave_comparison_data(data: &[WaveComparisonData], format: OutputFormat) {
+    write_rows("wave_comparison", data, format);
+}
+
+pub fn write_power_analysis_data(data: &PowerAnalysisData, format: OutputFormat) {
+    write_data("power_analysis_data", data, format);
+}
+
+pub fn write_weighting_sensitivity_data(data: &WeightingSensitivityData, format: OutputFormat) {
+    write_data("weighting_sensitivity_data", data, format);
+}
+
+/// The consolidated document is always JSON, whatever the output format of the other files.
+pub fn write_results_data(data: &ResultsData) {
+    write_data("results", data, OutputFormat::Json);
+}
+
+pub fn write_result_comparison_data(data: &ResultComparisonData, format: OutputFormat) {
+    write_data("result_comparison", data, format);
+}
+
+/// Reads the results of an earlier run from a `results.json` file or from a result
+/// directory. A directory without `results.json` falls back to the separate JSON files.
+pub fn read_comparable_results(path: &str) -> Result<ComparableResultsData, Box<dyn Error>> {
+    let path = Path::new(path);
+    if path.is_file() {
+        return read_json(path);
+    }
+    if path.join("results.json").is_file() {
+        return read_json(&path.join("results.json"));
+    }
+
+    Ok(ComparableResultsData {
+        correlations: read_json(&path.join("correlation_data.json"))?,
+        impacts: read_json(&path.join("impact_data.json"))?,
+        diffs: read_json(&path.join("diffs.json"))?,
+    })
+}
+
+pub fn write_respondent_scores(data: &Vec<RespondentScoreData>, format: ExportFormat) {
+    match format {
+        ExportFormat::Json => write_data("respondent_scores", data, OutputFormat::Json),
+        ExportFormat::Csv => write_data("respondent_scores", data, OutputFormat::Csv),
+        ExportFormat::Tsv => write_data("respondent_scores", data, OutputFormat::Tsv),
+        ExportFormat::Parquet => write_respondent_scores_parquet(data).unwrap(),
+    }
+}
+
+pub fn write_simulated_survey(survey: &SimulatedSurvey) {
+    create_dir_all(OUT_DIR).unwrap();
+    write_survey_csv(survey, &format!("{}/simulated_feedbacks.csv", OUT_DIR)).unwrap();
+}
+
+/// Writes survey responses as a form export that `read_csv` accepts.
+pub fn write_survey_csv(survey: &SimulatedSurvey, path: &str) -> Result<(), Box<dyn Error>> {
+    let mut writer = WriterBuilder::new().from_path(path)?;
+    writer.write_record(&survey.headers)?;
+    for row in survey.rows.iter() {
+        writer.write_record(row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn write_html_report(html: &str) {
+    create_dir_all(OUT_DIR).unwrap();
+    fs::write(format!("{}/report.html", OUT_DIR), html).unwrap();
 }
 
-fn write_data<T: Serialize>(filename: &str, data: &Vec<T>) {
+pub fn write_markdown_report(markdown: &str) {
     create_dir_all(OUT_DIR).unwrap();
-    let file: File = File::create(filename).unwrap();
-    let mut writer: BufWriter<File> = BufWriter::new(file);
-    serde_json::to_writer_pretty(&mut writer, data).unwrap();
+    fs::write(format!("{}/report.md", OUT_DIR), markdown).unwrap();
+}
+
+pub fn write_latex_tables(latex: &str) {
+    create_dir_all(OUT_DIR).unwrap();
+    fs::write(format!("{}/tables.tex", OUT_DIR), latex).unwrap();
+}
+
+fn write_respondent_scores_parquet(data: &[RespondentScoreData]) -> Result<(), ParquetError> {
+    create_dir_all(OUT_DIR).unwrap();
+
+    let schema = Arc::new(parse_message_type(RESPONDENT_SCORES_SCHEMA)?);
+    let file: File = File::create(format!("{}/respondent_scores.parquet", OUT_DIR)).unwrap();
+    let mut writer = SerializedFileWriter::new(file, schema, Default::default())?;
+    let mut row_group = writer.next_row_group()?;
+    let info = |row: &RespondentScoreData, get: fn(&QuestionInfo) -> &String| {
+        row.question_info
+            .as_ref()
+            .map(|i| ByteArray::from(get(i).as_str()))
+    };
+
+    let mut column_index = 0;
+    while let Some(mut column) = row_group.next_column()? {
+        match column_index {
+            0 => write_optional_column::<Int64Type>(
+                &mut column,
+                data.iter().map(|r| r.respondent_id.map(|id| id as i64)),
+            )?,
+            1 => write_optional_column::<ByteArrayType>(
+                &mut column,
+                data.iter().map(|r| Some(ByteArray::from(r.wave.as_str()))),
+            )?,
+            2 => write_optional_column::<ByteArrayType>(
+                &mut column,
+                data.iter()
+                    .map(|r| Some(ByteArray::from(r.question.as_str()))),
+            )?,
+            3 => write_optional_column::<ByteArrayType>(
+                &mut column,
+                data.iter().map(|r| info(r, |i| &i.question_id)),
+            )?,
+            4 => write_optional_column::<ByteArrayType>(
+                &mut column,
+                data.iter().map(|r| info(r, |i| &i.question_text)),
+            )?,
+            5 => write_optional_column::<ByteArrayType>(
+                &mut column,
+                data.iter().map(|r| info(r, |i| &i.question_label)),
+            )?,
+            6 => write_optional_column::<ByteArrayType>(
+                &mut column,
+                data.iter().map(|r| info(r, |i| &i.question_category)),
+            )?,
+            7 => write_optional_column::<ByteArrayType>(
+                &mut column,
+                data.iter()
+                    .map(|r| Some(ByteArray::from(r.answer.as_str()))),
+            )?,
+            8 => write_optional_column::<Int32Type>(
+                &mut column,
+                data.iter().map(|r| Some(r.score as i32)),
+            )?,
+            9 => write_optional_column::<DoubleType>(&mut column, data.iter().map(|r| r.rank))?,
+            10 => write_optional_column::<Int32Type>(
+                &mut column,
+                data.iter().map(|r| Some(r.quality as i32)),
+            )?,
+            _ => write_optional_column::<DoubleType>(
+                &mut column,
+                data.iter().map(|r| r.quality_rank),
+            )?,
+        }
+        column.close()?;
+        column_index += 1;
+    }
+
+    row_group.close()?;
+    writer.close()?;
+    Ok(())
+}
+
+/// Every column in the schema is optional, so missing values are written as nulls.
+fn write_optional_column<T: DataType>(
+    column: &mut SerializedColumnWriter,
+    values: impl Iterator<Item = Option<T::T>>,
+) -> Result<(), ParquetError> {
+    let mut present_values: Vec<T::T> = Vec::new();
+    let mut definition_levels: Vec<i16> = Vec::new();
+    for value in values {
+        definition_levels.push(value.is_some() as i16);
+        present_values.extend(value);
+    }
+
+    column
+        .typed::<T>()
+        .write_batch(&present_values, Some(&definition_levels), None)?;
+    Ok(())
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, Box<dyn Error>> {
+    let file: File = File::open(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    let data: T = serde_json::from_reader(BufReader::new(file))
+        .map_err(|err| format!("{}: {}", path.display(), err))?;
+    Ok(data)
+}
+
+fn write_data<T: Serialize + ?Sized>(name: &str, data: &T, format: OutputFormat) {
+    create_dir_all(OUT_DIR).unwrap();
+
+    match format {
+        OutputFormat::Json => {
+            let file: File = File::create(format!("{}/{}.json", OUT_DIR, name)).unwrap();
+            let mut writer: BufWriter<File> = BufWriter::new(file);
+            serde_json::to_writer_pretty(&mut writer, data).unwrap();
+            writer.flush().unwrap();
+        }
+        OutputFormat::Csv | OutputFormat::Tsv => {
+            // Through JSON text, `f32` fields keep their shortest form instead of being widened
+            let value: Value = serde_json::from_str(&serde_json::to_string(data).unwrap()).unwrap();
+            for (table_name, rows) in get_tables(name, &value) {
+                write_table(&table_name, &rows, format);
+            }
+        }
+    }
+}
+
+/// Writes rows as CSV or TSV. Every row of a table serialises the same fields, so the columns
+/// are the field order of the serialised structs, whatever the values.
+/// Writes a list of result rows. Without rows, CSV and TSV still get a header row with the
+/// fields of a default row, so an empty table is told apart from a missing file.
+fn write_rows<T: Serialize + Default>(name: &str, rows: &[T], format: OutputFormat) {
+    if !rows.is_empty() || format == OutputFormat::Json {
+        write_data(name, rows, format);
+        return;
+    }
+
+    create_dir_all(OUT_DIR).unwrap();
+    let columns = get_default_columns::<T>();
+    write_columns(name, &columns.iter().collect::<Vec<_>>(), &[], format);
+}
+
+/// The table columns of a default row. Its lists are empty child tables, which have no
+/// columns in the table itself.
+pub fn get_default_columns<T: Serialize + Default>() -> Vec<String> {
+    match serde_json::to_value(T::default()).unwrap() {
+        Value::Object(template) => template
+            .into_iter()
+            .filter(|(_, field)| !field.is_array())
+            .map(|(key, _)| key)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn write_table(name: &str, rows: &[Map<String, Value>], format: OutputFormat) {
+    let columns: Vec<&String> = rows
+        .first()
+        .map(|row| row.keys().collect())
+        .unwrap_or_default();
+    write_columns(name, &columns, rows, format);
+}
+
+fn write_columns(
+    name: &str,
+    columns: &[&String],
+    rows: &[Map<String, Value>],
+    format: OutputFormat,
+) {
+    let (delimiter, extension) = match format {
+        OutputFormat::Tsv => (b'\t', "tsv"),
+        _ => (b',', "csv"),
+    };
+
+    let mut writer = WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_path(format!("{}/{}.{}", OUT_DIR, name, extension))
+        .unwrap();
+    writer.write_record(columns).unwrap();
+    for row in rows {
+        writer
+            .write_record(
+                columns
+                    .iter()
+                    .map(|column| get_cell(row.get(*column).unwrap_or(&Value::Null))),
+            )
+            .unwrap();
+    }
     writer.flush().unwrap();
 }
+
+/// Flattens a serialised result into tidy tables. Nested lists of objects become their own
+/// tables named `{parent}_{field}`, each row prefixed with the scalar fields of its parent.
+/// Lists of scalars stay in their own row, joined by `;`.
+fn get_tables(name: &str, value: &Value) -> Vec<(String, Vec<Map<String, Value>>)> {
+    let items: Vec<&Value> = match value {
+        Value::Array(items) => items.iter().collect(),
+        _ => vec![value],
+    };
+
+    // A list of objects is a child table even where it is empty
+    let child_keys: Vec<&String> = items
+        .iter()
+        .filter_map(|item| item.as_object())
+        .flat_map(|object| object.iter())
+        .filter(|(_, field)| match field {
+            Value::Array(values) => values.iter().any(Value::is_object),
+            Value::Object(_) => true,
+            _ => false,
+        })
+        .map(|(key, _)| key)
+        .collect();
+
+    let mut rows: Vec<Map<String, Value>> = Vec::new();
+    let mut child_tables: Vec<(String, Vec<Map<String, Value>>)> = Vec::new();
+
+    for item in items {
+        let Value::Object(object) = item else {
+            let mut row = Map::new();
+            row.insert(name.to_owned(), item.clone());
+            rows.push(row);
+            continue;
+        };
+
+        let mut row = Map::new();
+        let mut children: Vec<(&String, &Value)> = Vec::new();
+        for (key, field) in object {
+            if child_keys.contains(&key) {
+                // Without a nested result there are no child rows
+                if !field.is_null() {
+                    children.push((key, field));
+                }
+            } else {
+                row.insert(key.to_owned(), field.clone());
+            }
+        }
+
+        let context: Map<String, Value> = row
+            .iter()
+            .filter(|(_, field)| !field.is_array())
+            .map(|(key, field)| (key.to_owned(), field.clone()))
+            .collect();
+
+        for (key, field) in children {
+            for (child_name, child_rows) in get_tables(&format!("{}_{}", name, key), field) {
+                let prefixed_rows = child_rows.into_iter().map(|child_row| {
+                    let mut prefixed_row = context.clone();
+                    prefixed_row.extend(child_row);
+                    prefixed_row
+                });
+
+                match child_tables.iter_mut().find(|(n, _)| *n == child_name) {
+                    Some((_, existing_rows)) => existing_rows.extend(prefixed_rows),
+                    None => child_tables.push((child_name, prefixed_rows.collect())),
+                }
+            }
+        }
+
+        rows.push(row);
+    }
+
+    let mut tables = Vec::new();
+    if rows.iter().any(|row| !row.is_empty()) {
+        tables.push((name.to_owned(), rows));
+    }
+    tables.extend(child_tables);
+    tables
+}
+
+fn get_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.to_owned(),
+        Value::Array(values) => values
+            .iter()
+            .map(get_cell)
+            .collect::<Vec<String>>()
+            .join(";"),
+        _ => value.to_string(),
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{
+    fns::{
+        diff::get_rho_median,
+        question::{get_question_label, get_question_text},
+        svg::{get_forest_plot, get_likert_chart, get_scatter_plot},
+        util::{format_value, get_two_sided_p_value},
+    },
+    util::{
+        types::{
+            ImpactDistributionData, QuestionCorrelationData, QuestionDataDiffs,
+            QuestionFrequencyData, QuestionImpactData,
+        },
+        vars,
+    },
+};
+use std::fmt::Write;
+
+static REPORT_STYLE: &str = "body{font-family:sans-serif;margin:2em auto;max-width:60em;color:#222}\
+table{border-collapse:collapse;margin-bottom:2em}\
+th,td{border-bottom:1px solid #ddd;padding:.25em .75em;text-align:right}\
+th:first-child,td:first-child{text-align:left}\
+tr.significant td{font-weight:bold}";
+
+/// Builds a self-contained HTML report; all charts are inline SVG so the file works offline.
+pub fn get_html_report(
+    correlations: &[QuestionCorrelationData],
+    impacts: &[QuestionImpactData],
+    impact_average: f64,
+    diffs: &[QuestionDataDiffs],
+    distributions: &[ImpactDistributionData],
+    frequencies: &[QuestionFrequencyData],
+    precision: usize,
+) -> String {
+    let (rho_threshold, impact_threshold) = (get_rho_median(correlations), impact_average);
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        "<!DOCTYPE html><html lang=\"en\"><head><meta charset=\"utf-8\"><title>{0}</title><style>{1}</style></head><body><h1>{0}</h1>",
+        "Development practice survey report", REPORT_STYLE
+    );
+    let _ = write!(
+        html,
+        "<p>Overall impact average: {}. Rows in bold are significant at two-sided p &lt; {}.</p>",
+        format_value(precision, impact_average),
+        vars::SIGNIFICANCE_LEVEL
+    );
+
+    html.push_str("<h2>Correlation with quality</h2>");
+    html.push_str(&get_forest_plot(correlations));
+    write_table_start(
+        &mut html,
+        &[
+            "Question",
+            "rho",
+            "CI lower",
+            "CI upper",
+            "p (two-sided)",
+            "n",
+        ],
+    );
+    for correlation in correlations {
+        write_row(
+            &mut html,
+            get_two_sided_p_value(correlation.p_value) < vars::SIGNIFICANCE_LEVEL,
+            &[
+                escape_html(get_question_label(&correlation.question)),
+                format_value(precision, correlation.rho),
+                format_value(precision, correlation.rho_ci_lower),
+                format_value(precision, correlation.rho_ci_upper),
+                format_value(precision, get_two_sided_p_value(correlation.p_value)),
+                correlation.answer_count.to_string(),
+            ],
+        );
+    }
+    html.push_str("</tbody></table>");
+
+    html.push_str("<h2>Perceived impact</h2>");
+    html.push_str(&get_likert_chart(distributions));
+    write_table_start(&mut html, &["Question", "Impact average", "n"]);
+    for impact in impacts {
+        write_row(
+            &mut html,
+            false,
+            &[
+                escape_html(get_question_label(&impact.question)),
+                format_value(precision, impact.impact_average as f64),
+                impact.answer_count.to_string(),
+            ],
+        );
+    }
+    html.push_str("</tbody></table>");
+
+    html.push_str("<h2>Correlation versus perceived impact</h2>");
+    let _ = write!(
+        html,
+        "<p>Quadrants are split at the median rho ({}) and the overall impact average ({}).</p>",
+        format_value(precision, rho_threshold),
+        format_value(precision, impact_threshold)
+    );
+    html.push_str(&get_scatter_plot(diffs, rho_threshold, impact_threshold));
+    write_table_start(
+        &mut html,
+        &[
+            "Question",
+            "rho",
+            "p (two-sided)",
+            "Impact average",
+            "Diff",
+            "Quadrant",
+        ],
+    );
+    for diff in diffs {
+        write_row(
+            &mut html,
+            get_two_sided_p_value(diff.p_value) < vars::SIGNIFICANCE_LEVEL,
+            &[
+                escape_html(get_question_label(&diff.question_correlation)),
+                format_value(precision, diff.rho),
+                format_value(precision, get_two_sided_p_value(diff.p_value)),
+                format_value(precision, diff.impact_average as f64),
+                format_value(precision, diff.diff),
+                diff.quadrant.get_label().to_owned(),
+            ],
+        );
+    }
+    html.push_str("</tbody></table>");
+
+    html.push_str("<h2>Answer distributions</h2>");
+    write_frequency_tables(&mut html, frequencies, precision);
+    html.push_str("</body></html>");
+
+    html
+}
+
+/// A score distribution table over all questions, then each question's answers and
+/// score by quality crosstab in a collapsible section.
+fn write_frequency_tables(
+    html: &mut String,
+    frequencies: &[QuestionFrequencyData],
+    precision: usize,
+) {
+    let Some(first) = frequencies.first() else {
+        return;
+    };
+
+    let mut headers: Vec<String> = vec!["Question".to_owned()];
+    headers.extend(first.scores.iter().map(|s| format!("Score {}", s.score)));
+    write_table_start(
+        html,
+        &headers.iter().map(String::as_str).collect::<Vec<&str>>(),
+    );
+    for frequency in frequencies {
+        let mut cells: Vec<String> = vec![escape_html(get_question_label(&frequency.question))];
+        cells.extend(
+            frequency
+                .scores
+                .iter()
+                .map(|s| format!("{} ({}%)", s.count, format_value(precision, s.percentage))),
+        );
+        write_row(html, false, &cells);
+    }
+    html.push_str("</tbody></table>");
+
+    for frequency in frequencies {
+        let _ = write!(
+            html,
+            "<details><summary>{}</summary>",
+            escape_html(get_question_text(&frequency.question))
+        );
+
+        write_table_start(html, &["Answer", "Score", "Count", "%"]);
+        for answer in frequency.answers.iter() {
+            write_row(
+                html,
+                false,
+                &[
+                    escape_html(&answer.answer),
+                    answer.score.to_string(),
+                    answer.count.to_string(),
+                    format_value(precision, answer.percentage),
+                ],
+            );
+        }
+        html.push_str("</tbody></table>");
+
+        let mut qualities: Vec<i8> = frequency.crosstab.iter().map(|c| c.quality).collect();
+        qualities.sort();
+        qualities.dedup();
+        let mut headers: Vec<String> = vec!["Score / quality".to_owned()];
+        headers.extend(qualities.iter().map(|q| q.to_string()));
+        write_table_start(
+            html,
+            &headers.iter().map(String::as_str).collect::<Vec<&str>>(),
+        );
+        for score in frequency.scores.iter() {
+            let mut cells: Vec<String> = vec![score.score.to_string()];
+            cells.extend(
+                frequency
+                    .crosstab
+                    .iter()
+                    .filter(|c| c.score == score.score)
+                    .map(|c| c.count.to_string()),
+            );
+            write_row(html, false, &cells);
+        }
+        html.push_str("</tbody></table></details>");
+    }
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn write_table_start(html: &mut String, headers: &[&str]) {
+    html.push_str("<table><thead><tr>");
+    for header in headers {
+        let _ = write!(html, "<th>{}</th>", header);
+    }
+    html.push_str("</tr></thead><tbody>");
+}
+
+fn write_row(html: &mut String, is_significant: bool, cells: &[String]) {
+    html.push_str(if is_significant {
+        "<tr class=\"significant\">"
+    } else {
+        "<tr>"
+    });
+    for cell in cells {
+        let _ = write!(html, "<td>{}</td>", cell);
+    }
+    html.push_str("</tr>");
+}
@@ -1,63 +1,28 @@
-use crate::util::{
-    types::{Feedback, QuestionImpactData},
-    vars,
+use crate::{
+    fns::question::{get_question_info, get_questions_of_kind},
+    util::{
+        types::{Feedback, ImpactDistributionData, QuestionImpactData, QuestionKind},
+        vars,
+    },
 };
-use std::collections::HashMap;
-
-macro_rules! handle_feedback {
-  ( $impact_map:expr, $feedbacks:expr, $($field:ident),* ) => {
-        for feedback in $feedbacks {
-            $(
-                if let Ok(v) = feedback.$field.parse::<f32>() {
-                    $impact_map.entry(stringify!($field).to_owned()).or_insert(vec![]).push(v);
-                }
-            )*
-        }
-    }
-}
 
 pub fn get_impact_averages(feedbacks: &Vec<Feedback>) -> Vec<QuestionImpactData> {
-    let mut impact_map: HashMap<String, Vec<f32>> = HashMap::new();
+    let mut impact_ratings: Vec<(&'static str, Vec<f32>)> =
+        get_questions_of_kind(QuestionKind::Impact)
+            .map(|question| (question.field_name, Vec::new()))
+            .collect();
 
-    handle_feedback!(
-        impact_map,
-        feedbacks,
-        impact_writing_requirements_documents,
-        impact_writing_initial_project_plan,
-        impact_writing_specification_documents,
-        impact_writing_technical_design_documents,
-        impact_writing_sbom_document,
-        impact_writing_draft_design_documents,
-        impact_requiring_document_reviewers,
-        impact_avoiding_mutability,
-        impact_encoding_mutable_names,
-        impact_nulls,
-        impact_using_english,
-        impact_code_style,
-        impact_code_refactoring,
-        impact_posix_timestamps,
-        impact_magic_numbers,
-        impact_code_comments,
-        impact_regex_comments,
-        impact_avoiding_todo_comments,
-        impact_reviewing_ai_code,
-        impact_branching_strategy,
-        impact_branch_naming_strategy,
-        impact_commit_message_format,
-        impact_merging_strategy,
-        impact_higher_level_design,
-        impact_requiring_reviewer,
-        impact_formatter_linter_tools,
-        impact_static_analysis_tools,
-        impact_centralising_tools,
-        impact_semi_automated_ci_cd,
-        impact_automated_tests,
-        impact_tests_like_production,
-        impact_unit_tests_boc
-    );
+    for feedback in feedbacks {
+        for ((_, ratings), (_, rating)) in
+            impact_ratings.iter_mut().zip(get_impact_ratings(feedback))
+        {
+            ratings.extend(rating);
+        }
+    }
 
-    let mut impact_data: Vec<QuestionImpactData> = impact_map
+    let mut impact_data: Vec<QuestionImpactData> = impact_ratings
         .iter()
+        .filter(|(_, value)| !value.is_empty())
         .map(|(key, value)| {
             let impact_sum: f32 = value.iter().sum::<f32>();
             let answer_count: usize = value.len();
@@ -68,7 +33,8 @@ pub fn get_impact_averages(feedbacks: &Vec<Feedback>) -> Vec<QuestionImpactData>
                     / vars::ROUND_DECIMAL_PLACES_MULTIPLIER as f32;
 
             QuestionImpactData {
-                question: key.to_owned(),
+                question: (*key).to_owned(),
+                question_info: get_question_info(key),
                 impact_average: impact_average_rounded,
                 answer_count,
             }
@@ -78,6 +44,20 @@ pub fn get_impact_averages(feedbacks: &Vec<Feedback>) -> Vec<QuestionImpactData>
     impact_data
 }
 
+/// Returns the impact ratings of a response as `(question, rating)` pairs in survey order.
+/// Answers that are not numbers are `None`.
+pub fn get_impact_ratings(feedback: &Feedback) -> Vec<(&'static str, Option<f32>)> {
+    get_questions_of_kind(QuestionKind::Impact)
+        .map(|question| {
+            (
+                question.field_name,
+                question.get_score(feedback).map(|rating| rating as f32),
+            )
+        })
+        .collect()
+}
+
+#[allow(clippy::ptr_arg)]
 pub fn get_impact_average(data: &Vec<QuestionImpactData>) -> f64 {
     let impacts_sum: f64 = data.iter().map(|x| x.impact_average as f64).sum();
     let question_count: usize = data.len();
@@ -89,3 +69,30 @@ pub fn get_impact_average(data: &Vec<QuestionImpactData>) -> f64 {
 
     impact_average_rounded
 }
+
+/// Counts how often each rating on the impact scale was given per question,
+/// in the order the questions appear in the survey.
+pub fn get_impact_distributions(feedbacks: &[Feedback]) -> Vec<ImpactDistributionData> {
+    let ratings: Vec<f32> = (vars::IMPACT_RATING_MIN..=vars::IMPACT_RATING_MAX)
+        .map(|r| r as f32)
+        .collect();
+    let mut distributions: Vec<ImpactDistributionData> = Vec::new();
+
+    for feedback in feedbacks {
+        for (i, (question, rating)) in get_impact_ratings(feedback).into_iter().enumerate() {
+            if distributions.len() <= i {
+                distributions.push(ImpactDistributionData {
+                    question: question.to_owned(),
+                    ratings: ratings.clone(),
+                    counts: vec![0; ratings.len()],
+                });
+            }
+
+            if let Some(index) = rating.and_then(|r| ratings.iter().position(|x| *x == r)) {
+                distributions[i].counts[index] += 1;
+            }
+        }
+    }
+
+    distributions
+}
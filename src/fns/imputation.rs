@@ -0,0 +1,221 @@
+use crate::{
+    fns::{
+        question::get_question_info, rank::get_spearman_correlation, stats::get_pooled_correlation,
+        util::round_value,
+    },
+    util::{
+        types::{QuestionCorrelationData, ScoreColumns},
+        vars,
+    },
+};
+use nalgebra::{DMatrix, DVector};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+/// Observed answers whose predicted scores are closest to that of a missing answer, one of
+/// which donates its score.
+static DONOR_COUNT: usize = 5;
+/// Share of the diagonal added to the normal equations so collinear practices still fit.
+static RIDGE_PENALTY: f64 = 1e-5;
+
+/// Spearman's rho of every practice question with the quality estimate over all respondents,
+/// with the unscored answers imputed by chained equations and the correlations of the
+/// `imputation_count` imputed datasets pooled by Rubin's rules. Questions without applicable
+/// answers, or whose rho is undefined in an imputed dataset, are skipped.
+pub fn get_pooled_correlations(
+    columns: &ScoreColumns,
+    imputation_count: usize,
+    iteration_count: usize,
+) -> Vec<QuestionCorrelationData> {
+    let respondent_count = columns.get_respondent_count();
+    let qualities: Vec<f64> = columns.qualities.iter().map(|q| *q as f64).collect();
+    let mut rng = StdRng::seed_from_u64(vars::RANDOM_SEED);
+
+    let imputed_rhos: Vec<Vec<f64>> = (0..imputation_count)
+        .map(|_| {
+            get_imputed_scores(columns, iteration_count, &mut rng)
+                .iter()
+                .map(|scores| {
+                    scores
+                        .as_ref()
+                        .map_or(f64::NAN, |s| get_spearman_correlation(s, &qualities).0)
+                })
+                .collect()
+        })
+        .collect();
+
+    columns
+        .questions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, column)| {
+            let rhos: Vec<f64> = imputed_rhos.iter().map(|rhos| rhos[index]).collect();
+            let (rho, rho_ci_lower, rho_ci_upper, p_value) =
+                get_pooled_correlation(&rhos, respondent_count)?;
+
+            Some(QuestionCorrelationData {
+                question: column.question.to_owned(),
+                question_info: get_question_info(column.question),
+                rho: round_value(rho),
+                rho_ci_lower: round_value(rho_ci_lower),
+                rho_ci_upper: round_value(rho_ci_upper),
+                p_value: round_value(p_value),
+                p_value_adjusted: round_value(p_value),
+                answer_count: columns.get_applicable_scores(column).0.len(),
+                imputed_answer_count: Some(respondent_count),
+            })
+        })
+        .collect()
+}
+
+/// One imputed dataset: the scores of every question, or `None` for a question without
+/// applicable answers. Missing scores start as random observed scores of the question, then
+/// each iteration imputes every question in turn by predictive mean matching on the other
+/// questions and the quality estimate, so imputed scores are always observed answers.
+fn get_imputed_scores(
+    columns: &ScoreColumns,
+    iteration_count: usize,
+    rng: &mut StdRng,
+) -> Vec<Option<Vec<f64>>> {
+    let observed: Vec<Vec<bool>> = columns
+        .questions
+        .iter()
+        .map(|column| {
+            column
+                .scores
+                .iter()
+                .map(|score| *score != vars::WEIGHTING_NOT_APPLICABLE)
+                .collect()
+        })
+        .collect();
+    let imputed: Vec<usize> = (0..columns.questions.len())
+        .filter(|j| observed[*j].contains(&true))
+        .collect();
+
+    let mut values: Vec<Vec<f64>> = columns
+        .questions
+        .iter()
+        .zip(observed.iter())
+        .map(|(column, is_observed)| {
+            let observed_scores: Vec<f64> = column
+                .scores
+                .iter()
+                .filter(|score| **score != vars::WEIGHTING_NOT_APPLICABLE)
+                .map(|score| *score as f64)
+                .collect();
+
+            column
+                .scores
+                .iter()
+                .zip(is_observed.iter())
+                .map(
+                    |(score, is_observed)| match (is_observed, observed_scores.len()) {
+                        (true, _) => *score as f64,
+                        (false, 0) => f64::NAN,
+                        (false, count) => observed_scores[rng.random_range(0..count)],
+                    },
+                )
+                .collect()
+        })
+        .collect();
+
+    for _ in 0..iteration_count {
+        for target in imputed.iter() {
+            if !observed[*target].contains(&false) {
+                continue;
+            }
+
+            let predictors: Vec<&Vec<f64>> = imputed
+                .iter()
+                .filter(|j| *j != target)
+                .map(|j| &values[*j])
+                .collect();
+            let imputations = get_matched_imputations(
+                &values[*target],
+                &observed[*target],
+                &predictors,
+                columns,
+                rng,
+            );
+
+            for (respondent, value) in imputations {
+                values[*target][respondent] = value;
+            }
+        }
+    }
+
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(j, scores)| imputed.contains(&j).then_some(scores))
+        .collect()
+}
+
+/// Predictive mean matching: regresses the observed scores on the predictors and the quality
+/// estimate, once on the observed answers and once on a bootstrap sample of them to carry the
+/// uncertainty of the fit into the imputations. Each missing answer takes the score of a
+/// random one of the `DONOR_COUNT` observed answers predicted closest to it.
+fn get_matched_imputations(
+    scores: &[f64],
+    observed: &[bool],
+    predictors: &[&Vec<f64>],
+    columns: &ScoreColumns,
+    rng: &mut StdRng,
+) -> Vec<(usize, f64)> {
+    let respondent_count = scores.len();
+    let design = DMatrix::from_fn(respondent_count, predictors.len() + 2, |i, j| match j {
+        0 => 1f64,
+        1 => columns.qualities[i] as f64,
+        j => predictors[j - 2][i],
+    });
+
+    let donors: Vec<usize> = (0..respondent_count).filter(|i| observed[*i]).collect();
+    let resampled: Vec<usize> = (0..donors.len())
+        .map(|_| donors[rng.random_range(0..donors.len())])
+        .collect();
+
+    let fit = get_regression_coefficients(&design, scores, &donors);
+    let resampled_fit = get_regression_coefficients(&design, scores, &resampled);
+    let donor_predictions: Vec<f64> = donors
+        .iter()
+        .map(|i| design.row(*i).transpose().dot(&fit))
+        .collect();
+
+    (0..respondent_count)
+        .filter(|i| !observed[*i])
+        .map(|i| {
+            let prediction = design.row(i).transpose().dot(&resampled_fit);
+            let mut closest: Vec<usize> = (0..donors.len()).collect();
+            closest.sort_by(|a, b| {
+                (donor_predictions[*a] - prediction)
+                    .abs()
+                    .partial_cmp(&(donor_predictions[*b] - prediction).abs())
+                    .unwrap()
+            });
+            closest.truncate(DONOR_COUNT);
+
+            let donor = donors[closest[rng.random_range(0..closest.len())]];
+            (i, scores[donor])
+        })
+        .collect()
+}
+
+/// Ridge-stabilised least-squares coefficients of `scores` on the design rows of `rows`, or
+/// zeros when the normal equations cannot be solved.
+fn get_regression_coefficients(
+    design: &DMatrix<f64>,
+    scores: &[f64],
+    rows: &[usize],
+) -> DVector<f64> {
+    let x = design.select_rows(rows);
+    let y = DVector::from_iterator(rows.len(), rows.iter().map(|i| scores[*i]));
+
+    let mut normal = x.transpose() * &x;
+    for j in 0..normal.ncols() {
+        normal[(j, j)] += RIDGE_PENALTY * normal[(j, j)].max(1f64);
+    }
+
+    normal
+        .cholesky()
+        .map(|cholesky| cholesky.solve(&(x.transpose() * y)))
+        .unwrap_or_else(|| DVector::zeros(design.ncols()))
+}
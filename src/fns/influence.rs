@@ -0,0 +1,107 @@
+use crate::{
+    fns::{
+        rank::{get_correlations, get_spearman_correlation},
+        score::get_score_columns,
+        util::round_value,
+    },
+    util::{
+        types::{Feedback, QuestionInfluenceData, RespondentInfluenceData, ScoreColumns},
+        vars,
+    },
+};
+
+/// Recomputes Spearman's rho of every practice question with each applicable respondent left
+/// out, to show how far single respondents drive a finding. Leave-one-out samples with too
+/// few answers or without variation have no correlation and are skipped.
+pub fn calculate_influences(feedbacks: &[Feedback]) -> Vec<QuestionInfluenceData> {
+    let columns: ScoreColumns = get_score_columns(feedbacks);
+
+    get_correlations(&columns)
+        .into_iter()
+        .map(|correlation| {
+            let column = columns
+                .questions
+                .iter()
+                .find(|c| c.question == correlation.question)
+                .unwrap();
+            let respondents: Vec<usize> = (0..columns.get_respondent_count())
+                .filter(|i| column.scores[*i] != vars::WEIGHTING_NOT_APPLICABLE)
+                .collect();
+            let (scores, qualities) = columns.get_applicable_scores(column);
+            let (rho, p_value) = get_spearman_correlation(&scores, &qualities);
+            let is_significant = p_value < vars::SIGNIFICANCE_LEVEL;
+
+            let mut influences: Vec<RespondentInfluenceData> = respondents
+                .iter()
+                .zip(get_leave_one_out_correlations(&scores, &qualities))
+                .filter_map(|(respondent, correlation)| {
+                    let (rho_without, p_value_without) = correlation?;
+                    Some(RespondentInfluenceData {
+                        respondent_id: columns.ids[*respondent],
+                        rho: round_value(rho_without),
+                        p_value: round_value(p_value_without),
+                        rho_change: round_value(rho_without - rho),
+                        is_significance_changed: (p_value_without < vars::SIGNIFICANCE_LEVEL)
+                            != is_significant,
+                    })
+                })
+                .collect();
+
+            let rho_min = influences
+                .iter()
+                .map(|i| i.rho)
+                .reduce(f64::min)
+                .unwrap_or(rho);
+            let rho_max = influences
+                .iter()
+                .map(|i| i.rho)
+                .reduce(f64::max)
+                .unwrap_or(rho);
+            let significance_changing_count = influences
+                .iter()
+                .filter(|i| i.is_significance_changed)
+                .count();
+
+            influences.sort_by(|a, b| b.rho_change.abs().partial_cmp(&a.rho_change.abs()).unwrap());
+            influences.truncate(vars::INFLUENTIAL_RESPONDENT_COUNT);
+
+            QuestionInfluenceData {
+                question: correlation.question,
+                question_info: correlation.question_info,
+                rho: correlation.rho,
+                p_value: correlation.p_value,
+                answer_count: correlation.answer_count,
+                rho_min: round_value(rho_min),
+                rho_max: round_value(rho_max),
+                significance_changing_count,
+                is_significance_changed: significance_changing_count > 0,
+                influential_respondents: influences,
+            }
+        })
+        .collect()
+}
+
+/// Rho and p-value without each answer in turn, or `None` where the remaining answers are
+/// too few or without variation.
+fn get_leave_one_out_correlations(scores: &[f64], qualities: &[f64]) -> Vec<Option<(f64, f64)>> {
+    let without = |values: &[f64], left_out: usize| -> Vec<f64> {
+        values
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != left_out)
+            .map(|(_, value)| *value)
+            .collect()
+    };
+
+    (0..scores.len())
+        .map(|left_out| {
+            if scores.len() <= 3 {
+                return None;
+            }
+
+            let (rho, p_value) =
+                get_spearman_correlation(&without(scores, left_out), &without(qualities, left_out));
+            (!rho.is_nan()).then_some((rho, p_value))
+        })
+        .collect()
+}
@@ -0,0 +1,146 @@
+use crate::{
+    fns::{
+        question::get_question_text,
+        util::{format_value, get_confidence_percentage, get_two_sided_p_value},
+    },
+    util::{
+        args::Args,
+        types::{QuestionCorrelationData, QuestionDataDiffs, QuestionImpactData},
+    },
+};
+use std::fmt::Write;
+
+static CORRELATIONS_TABLE: (&str, &str, &str) = (
+    "correlations",
+    "Spearman correlation of each practice with the estimated project quality",
+    "tab:correlations",
+);
+static IMPACTS_TABLE: (&str, &str, &str) = (
+    "impacts",
+    "Perceived impact of each practice on project quality",
+    "tab:impacts",
+);
+static DIFFS_TABLE: (&str, &str, &str) = (
+    "diffs",
+    "Correlation with quality compared with perceived impact",
+    "tab:diffs",
+);
+
+/// Builds `booktabs` tables of the correlations, impacts and diffs, ready for `\input`.
+/// Requires the `booktabs` package in the including document.
+pub fn get_latex_tables(
+    args: &Args,
+    correlations: &[QuestionCorrelationData],
+    impacts: &[QuestionImpactData],
+    diffs: &[QuestionDataDiffs],
+) -> String {
+    let format_number = |value: f64| format!("${}$", format_value(args.precision, value));
+    let mut latex = String::new();
+
+    write_table_start(&mut latex, args, CORRELATIONS_TABLE, "lrrrrr");
+    let _ = writeln!(
+        latex,
+        "Question & $\\rho$ & {}\\% CI & $p$ (two-sided) & $p_{{\\mathrm{{adj}}}}$ (two-sided) & $n$ \\\\\n\\midrule",
+        get_confidence_percentage()
+    );
+    for correlation in correlations {
+        let _ = writeln!(
+            latex,
+            "{} & {} & [{}, {}] & {} & {} & {} \\\\",
+            escape_latex(get_question_text(&correlation.question)),
+            format_number(correlation.rho),
+            format_number(correlation.rho_ci_lower),
+            format_number(correlation.rho_ci_upper),
+            format_number(get_two_sided_p_value(correlation.p_value)),
+            format_number(get_two_sided_p_value(correlation.p_value_adjusted)),
+            correlation.answer_count
+        );
+    }
+    write_table_end(&mut latex);
+
+    write_table_start(&mut latex, args, IMPACTS_TABLE, "lrr");
+    latex.push_str("Practice & Impact average & $n$ \\\\\n\\midrule\n");
+    for impact in impacts {
+        let _ = writeln!(
+            latex,
+            "{} & {} & {} \\\\",
+            escape_latex(get_question_text(&impact.question)),
+            format_number(impact.impact_average as f64),
+            impact.answer_count
+        );
+    }
+    write_table_end(&mut latex);
+
+    write_table_start(&mut latex, args, DIFFS_TABLE, "lrrrl");
+    latex.push_str("Question & $\\rho$ & Impact average & Diff & Quadrant \\\\\n\\midrule\n");
+    for diff in diffs {
+        let _ = writeln!(
+            latex,
+            "{} & {} & {} & {} & {} \\\\",
+            escape_latex(get_question_text(&diff.question_correlation)),
+            format_number(diff.rho),
+            format_number(diff.impact_average as f64),
+            format_number(diff.diff),
+            diff.quadrant.get_label()
+        );
+    }
+    write_table_end(&mut latex);
+
+    latex
+}
+
+fn write_table_start(
+    latex: &mut String,
+    args: &Args,
+    (name, default_caption, default_label): (&str, &str, &str),
+    columns: &str,
+) {
+    let caption = args
+        .table_captions
+        .get(name)
+        .map_or(default_caption, |c| c.as_str());
+    let label = args
+        .table_labels
+        .get(name)
+        .map_or(default_label, |l| l.as_str());
+
+    let _ = write!(
+        latex,
+        "\\begin{{table}}[htbp]\n\\centering\n\\caption{{{}}}\n\\label{{{}}}\n\\begin{{tabular}}{{{}}}\n\\toprule\n",
+        escape_latex(caption),
+        label,
+        columns
+    );
+}
+
+fn write_table_end(latex: &mut String) {
+    latex.push_str("\\bottomrule\n\\end{tabular}\n\\end{table}\n\n");
+}
+
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::new();
+    let mut is_code = false;
+
+    for c in text.chars() {
+        match c {
+            '`' => {
+                escaped.push_str(if is_code { "}" } else { "\\texttt{" });
+                is_code = !is_code;
+            }
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '"' => escaped.push_str("''"),
+            _ => escaped.push(c),
+        }
+    }
+    if is_code {
+        escaped.push('}');
+    }
+
+    escaped
+}
@@ -0,0 +1,194 @@
+use crate::{
+    fns::{
+        question::get_paired_field_name,
+        question::{QUESTION_CATEGORIES, get_question_category, get_question_text},
+        util::{
+            format_value, get_confidence_percentage, get_significance_marker, get_two_sided_p_value,
+        },
+    },
+    util::{
+        args::Args,
+        types::{
+            QuestionCorrelationData, QuestionDataDiffs, QuestionFrequencyData, QuestionImpactData,
+        },
+        vars,
+    },
+};
+use std::fmt::Write;
+
+/// Builds a Markdown report with one section per question category, meant for pasting into
+/// wikis and merge requests.
+pub fn get_markdown_report(
+    args: &Args,
+    respondent_count: usize,
+    correlations: &[QuestionCorrelationData],
+    impacts: &[QuestionImpactData],
+    impact_average: f64,
+    diffs: &[QuestionDataDiffs],
+    frequencies: &[QuestionFrequencyData],
+) -> String {
+    let mut markdown = String::from("# Development practice survey report\n\n");
+
+    let _ = writeln!(
+        markdown,
+        "| Parameter | Value |\n| --- | --- |\n| Version | {} {} |",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    );
+    for input in args.inputs.iter() {
+        let _ = writeln!(
+            markdown,
+            "| Input | {} (wave {}) |",
+            escape_markdown(&input.path),
+            escape_markdown(&input.wave)
+        );
+    }
+    if !args.group_columns.is_empty() {
+        let _ = writeln!(
+            markdown,
+            "| Group columns | {} |",
+            escape_markdown(&args.group_columns.join(", "))
+        );
+    }
+    let _ = writeln!(markdown, "| Respondents | {} |", respondent_count);
+    let _ = writeln!(
+        markdown,
+        "| Missing data | {} |",
+        escape_markdown(&args.missing_data.get_description())
+    );
+    let _ = writeln!(
+        markdown,
+        "| Significance level | {} |",
+        vars::SIGNIFICANCE_LEVEL
+    );
+    let _ = writeln!(
+        markdown,
+        "| Overall impact average | {} |",
+        format_value(args.precision, impact_average)
+    );
+
+    markdown.push_str("\nSignificance of rho by two-sided p: ");
+    let markers: Vec<String> = vars::SIGNIFICANCE_MARKERS
+        .iter()
+        .map(|(threshold, marker)| format!("`{}` p < {}", marker, threshold))
+        .collect();
+    markdown.push_str(&markers.join(", "));
+    markdown.push_str(".\n");
+
+    for category in QUESTION_CATEGORIES {
+        let rows: Vec<&QuestionCorrelationData> = correlations
+            .iter()
+            .filter(|c| get_question_category(&c.question) == category)
+            .collect();
+        if rows.is_empty() {
+            continue;
+        }
+
+        let _ = writeln!(markdown, "\n## {}\n", category);
+        let _ = writeln!(
+            markdown,
+            "| Question | rho | {}% CI | p (two-sided) | n | Impact average | Impact n | Quadrant |",
+            get_confidence_percentage()
+        );
+        markdown.push_str("| --- | ---: | ---: | ---: | ---: | ---: | ---: | --- |\n");
+
+        for correlation in rows {
+            let impact = impacts.iter().find(|i| {
+                Some(i.question.as_str()) == get_paired_field_name(&correlation.question)
+            });
+            let diff = diffs
+                .iter()
+                .find(|d| d.question_correlation == correlation.question);
+
+            let _ = writeln!(
+                markdown,
+                "| {} | {}{} | [{}, {}] | {} | {} | {} | {} | {} |",
+                escape_markdown(get_question_text(&correlation.question)),
+                format_value(args.precision, correlation.rho),
+                get_significance_marker(get_two_sided_p_value(correlation.p_value)),
+                format_value(args.precision, correlation.rho_ci_lower),
+                format_value(args.precision, correlation.rho_ci_upper),
+                format_value(args.precision, get_two_sided_p_value(correlation.p_value)),
+                correlation.answer_count,
+                impact.map_or(String::new(), |i| format_value(
+                    args.precision,
+                    i.impact_average as f64
+                )),
+                impact.map_or(String::new(), |i| i.answer_count.to_string()),
+                diff.map_or("", |d| d.quadrant.get_label())
+            );
+        }
+    }
+
+    write_frequency_tables(&mut markdown, args.precision, frequencies);
+
+    markdown
+}
+
+/// Score distributions as one table, then each question's score by quality crosstab
+/// in a collapsible block.
+fn write_frequency_tables(
+    markdown: &mut String,
+    precision: usize,
+    frequencies: &[QuestionFrequencyData],
+) {
+    let Some(first) = frequencies.first() else {
+        return;
+    };
+
+    markdown.push_str("\n## Answer distributions\n\n| Question |");
+    for score in first.scores.iter() {
+        let _ = write!(markdown, " Score {} |", score.score);
+    }
+    markdown.push_str("\n| --- |");
+    markdown.push_str(&" ---: |".repeat(first.scores.len()));
+    markdown.push('\n');
+
+    for frequency in frequencies {
+        let _ = write!(
+            markdown,
+            "| {} |",
+            escape_markdown(get_question_text(&frequency.question))
+        );
+        for score in frequency.scores.iter() {
+            let _ = write!(
+                markdown,
+                " {} ({}%) |",
+                score.count,
+                format_value(precision, score.percentage)
+            );
+        }
+        markdown.push('\n');
+    }
+
+    for frequency in frequencies {
+        let mut qualities: Vec<i8> = frequency.crosstab.iter().map(|c| c.quality).collect();
+        qualities.sort();
+        qualities.dedup();
+
+        let _ = write!(
+            markdown,
+            "\n<details><summary>{}</summary>\n\n| Score / quality |",
+            escape_markdown(get_question_text(&frequency.question))
+        );
+        for quality in qualities.iter() {
+            let _ = write!(markdown, " {} |", quality);
+        }
+        markdown.push_str("\n| --- |");
+        markdown.push_str(&" ---: |".repeat(qualities.len()));
+        markdown.push('\n');
+
+        for score in frequency.scores.iter() {
+            let _ = write!(markdown, "| {} |", score.score);
+            for cell in frequency.crosstab.iter().filter(|c| c.score == score.score) {
+                let _ = write!(markdown, " {} |", cell.count);
+            }
+            markdown.push('\n');
+        }
+        markdown.push_str("\n</details>\n");
+    }
+}
+
+fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|")
+}
@@ -0,0 +1,71 @@
+use crate::{
+    fns::{
+        question::{get_question_info, get_questions_of_kind},
+        stats::get_mann_whitney,
+        util::{get_missing_reason, round_value},
+    },
+    util::types::{
+        DontKnowQualityTestData, Feedback, MissingReason, QuestionKind, QuestionMissingData,
+    },
+};
+
+/// Counts the unscored answers of every practice question by reason. With `test_dont_know`,
+/// also tests whether the respondents who did not know differ in quality estimate from those
+/// who answered, which would make dropping them bias the correlation.
+pub fn calculate_missing_data(
+    feedbacks: &[Feedback],
+    test_dont_know: bool,
+) -> Vec<QuestionMissingData> {
+    get_questions_of_kind(QuestionKind::Practice)
+        .map(|question| {
+            let reasons: Vec<Option<MissingReason>> = feedbacks
+                .iter()
+                .map(|f| {
+                    f.get_answer(question.field_name)
+                        .and_then(get_missing_reason)
+                })
+                .collect();
+            let get_count =
+                |reason: MissingReason| reasons.iter().filter(|r| **r == Some(reason)).count();
+            let get_qualities = |is_included: fn(&Option<MissingReason>) -> bool| -> Vec<f64> {
+                feedbacks
+                    .iter()
+                    .zip(reasons.iter())
+                    .filter(|(_, reason)| is_included(reason))
+                    .map(|(f, _)| f.project_quality_estimate as f64)
+                    .collect()
+            };
+
+            let dont_know_quality_test = if test_dont_know {
+                let dont_know = get_qualities(|r| *r == Some(MissingReason::DontKnow));
+                let answered = get_qualities(Option::is_none);
+                get_mann_whitney(&answered, &dont_know).map(|(u, z, p_value)| {
+                    DontKnowQualityTestData {
+                        dont_know_quality_mean: round_value(get_mean(&dont_know)),
+                        answered_quality_mean: round_value(get_mean(&answered)),
+                        mann_whitney_u: round_value(u),
+                        z: round_value(z),
+                        p_value: round_value(p_value),
+                    }
+                })
+            } else {
+                None
+            };
+
+            QuestionMissingData {
+                question: question.field_name.to_owned(),
+                question_info: get_question_info(question.field_name),
+                respondent_count: feedbacks.len(),
+                answer_count: reasons.iter().filter(|r| r.is_none()).count(),
+                dont_know_count: get_count(MissingReason::DontKnow),
+                not_applicable_count: get_count(MissingReason::NotApplicable),
+                not_used_count: get_count(MissingReason::NotUsed),
+                dont_know_quality_test,
+            }
+        })
+        .collect()
+}
+
+fn get_mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
@@ -1,6 +1,30 @@
+pub mod cluster;
+pub mod compare;
 pub mod csv;
 pub mod diff;
+pub mod export;
+pub mod factor;
+pub mod frequency;
 pub mod fs;
+pub mod html;
 pub mod impact;
+pub mod imputation;
+pub mod influence;
+pub mod latex;
+pub mod markdown;
+pub mod missing;
+pub mod ordinal;
+pub mod power;
+pub mod question;
 pub mod rank;
+pub mod ranking;
+pub mod results;
+pub mod scaling;
+pub mod score;
+pub mod simulate;
+pub mod stats;
+pub mod subgroup;
+pub mod svg;
 pub mod util;
+pub mod wave;
+pub mod weighting;
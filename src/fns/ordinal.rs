@@ -0,0 +1,425 @@
+use crate::{
+    fns::{question::get_question_info, score::get_score_columns, util::round_value},
+    util::{
+        types::{Feedback, OrdinalThresholdData, QuestionOrdinalRegressionData, ScoreColumns},
+        vars,
+    },
+};
+use nalgebra::{DMatrix, DVector, Matrix2, Vector2};
+use statrs::distribution::{ChiSquared, ContinuousCDF, Normal};
+
+pub static ORDINAL_METHOD: &str = "proportional_odds_logit";
+static COMPOSITE_QUESTION_NAME: &str = "practice_score_mean";
+static HESSIAN_STEP: f64 = 1e-5;
+static MAX_STEP_HALVINGS: usize = 30;
+
+/// Fits a proportional-odds (cumulative logit) model of `project_quality_estimate`
+/// for every practice question, plus one for the mean practice score of each respondent.
+///
+/// The model is `P(Y <= j) = logistic(threshold_j - coefficient * score)`, so an odds ratio
+/// above one means higher practice scores go with higher quality categories.
+pub fn calculate_ordinal_regressions(feedbacks: &[Feedback]) -> Vec<QuestionOrdinalRegressionData> {
+    let columns: ScoreColumns = get_score_columns(feedbacks);
+    let mut results: Vec<QuestionOrdinalRegressionData> = Vec::new();
+
+    for column in columns.questions.iter() {
+        let (predictors, outcomes): (Vec<f64>, Vec<i8>) = column
+            .scores
+            .iter()
+            .zip(columns.qualities.iter())
+            .filter(|(score, _)| **score != vars::WEIGHTING_NOT_APPLICABLE)
+            .map(|(score, quality)| (*score as f64, *quality))
+            .unzip();
+
+        if let Some(result) = fit_ordinal_regression(column.question, &predictors, &outcomes) {
+            results.push(result);
+        }
+    }
+
+    let (predictors, outcomes): (Vec<f64>, Vec<i8>) = (0..columns.get_respondent_count())
+        .filter_map(|respondent| {
+            let applicable: Vec<f64> = columns
+                .get_respondent_scores(respondent)
+                .into_iter()
+                .filter(|score| *score != vars::WEIGHTING_NOT_APPLICABLE)
+                .map(|score| score as f64)
+                .collect();
+
+            if applicable.is_empty() {
+                return None;
+            }
+
+            let mean = applicable.iter().sum::<f64>() / applicable.len() as f64;
+            Some((mean, columns.qualities[respondent]))
+        })
+        .unzip();
+
+    if let Some(result) = fit_ordinal_regression(COMPOSITE_QUESTION_NAME, &predictors, &outcomes) {
+        results.push(result);
+    }
+
+    results.sort_by(|a, b| b.odds_ratio.partial_cmp(&a.odds_ratio).unwrap());
+    results
+}
+
+/// Fits the proportional-odds model of the outcome categories on one predictor by maximum
+/// likelihood, or `None` with fewer than three answers or a single category.
+pub fn fit_ordinal_regression(
+    question: &str,
+    predictors: &[f64],
+    outcomes: &[i8],
+) -> Option<QuestionOrdinalRegressionData> {
+    let mut categories: Vec<i8> = outcomes.to_vec();
+    categories.sort();
+    categories.dedup();
+
+    let answer_count = predictors.len();
+    let category_count = categories.len();
+
+    if answer_count < 3 || category_count < 2 {
+        return None;
+    }
+
+    let category_indices: Vec<usize> = outcomes
+        .iter()
+        .map(|y| categories.iter().position(|c| c == y).unwrap())
+        .collect();
+
+    let null_params = get_null_params(&category_indices, category_count);
+    let null_log_likelihood = get_log_likelihood(&null_params, predictors, &category_indices);
+    let (params, converged) = maximise_log_likelihood(null_params, predictors, &category_indices);
+    let log_likelihood = get_log_likelihood(&params, predictors, &category_indices);
+
+    let hessian = get_hessian(&params, predictors, &category_indices);
+    let covariance = (-hessian).try_inverse();
+    let standard_errors: Vec<f64> = (0..params.len())
+        .map(|i| match &covariance {
+            Some(c) => c[(i, i)].max(0f64).sqrt(),
+            None => f64::NAN,
+        })
+        .collect();
+
+    let coefficient = params[category_count - 1];
+    let standard_error = standard_errors[category_count - 1];
+
+    let normal = Normal::new(0f64, 1f64).expect("Failed to create Normal distribution");
+    let z_critical = normal.inverse_cdf(1f64 - vars::SIGNIFICANCE_LEVEL / 2f64);
+    let p_value = 2f64 * (1f64 - normal.cdf((coefficient / standard_error).abs()));
+
+    let likelihood_ratio_chi_squared = (2f64 * (log_likelihood - null_log_likelihood)).max(0f64);
+    let likelihood_ratio_p_value = 1f64
+        - ChiSquared::new(1f64)
+            .expect("Failed to create ChiSquared distribution")
+            .cdf(likelihood_ratio_chi_squared);
+
+    let thresholds: Vec<OrdinalThresholdData> = (0..category_count - 1)
+        .map(|j| OrdinalThresholdData {
+            boundary: format!("{}|{}", categories[j], categories[j + 1]),
+            estimate: round_value(params[j]),
+            standard_error: round_value(standard_errors[j]),
+        })
+        .collect();
+
+    let proportional_odds_df = category_count - 2;
+    let proportional_odds_chi_squared =
+        get_brant_statistic(predictors, &category_indices, category_count);
+    let proportional_odds_p_value = proportional_odds_chi_squared.map(|statistic| {
+        1f64 - ChiSquared::new(proportional_odds_df as f64)
+            .expect("Failed to create ChiSquared distribution")
+            .cdf(statistic)
+    });
+
+    Some(QuestionOrdinalRegressionData {
+        question: question.to_owned(),
+        question_info: get_question_info(question),
+        answer_count,
+        coefficient: round_value(coefficient),
+        standard_error: round_value(standard_error),
+        odds_ratio: round_value(coefficient.exp()),
+        odds_ratio_ci_lower: round_value((coefficient - z_critical * standard_error).exp()),
+        odds_ratio_ci_upper: round_value((coefficient + z_critical * standard_error).exp()),
+        p_value: round_value(p_value),
+        thresholds,
+        log_likelihood: round_value(log_likelihood),
+        null_log_likelihood: round_value(null_log_likelihood),
+        likelihood_ratio_chi_squared: round_value(likelihood_ratio_chi_squared),
+        likelihood_ratio_p_value: round_value(likelihood_ratio_p_value),
+        proportional_odds_chi_squared: proportional_odds_chi_squared.map(round_value),
+        proportional_odds_df,
+        proportional_odds_p_value: proportional_odds_p_value.map(round_value),
+        converged,
+    })
+}
+
+fn logistic(value: f64) -> f64 {
+    1f64 / (1f64 + (-value).exp())
+}
+
+fn logistic_density(value: f64) -> f64 {
+    let p = logistic(value);
+    p * (1f64 - p)
+}
+
+/// Thresholds at the logits of the cumulative category proportions and a zero coefficient,
+/// which is the maximum likelihood solution of the thresholds-only model.
+fn get_null_params(category_indices: &[usize], category_count: usize) -> Vec<f64> {
+    let answer_count = category_indices.len() as f64;
+    let mut params: Vec<f64> = Vec::new();
+    let mut cumulative_count = 0usize;
+
+    for category in 0..category_count - 1 {
+        cumulative_count += category_indices.iter().filter(|c| **c == category).count();
+        let proportion = cumulative_count as f64 / answer_count;
+        params.push((proportion / (1f64 - proportion)).ln());
+    }
+
+    params.push(0f64);
+    params
+}
+
+/// Returns the cumulative probability bounds `(P(Y <= category), P(Y <= category - 1))`
+/// as linear predictors, with infinities at the ends of the scale.
+fn get_linear_bounds(params: &[f64], predictor: f64, category: usize) -> (f64, f64) {
+    let threshold_count = params.len() - 1;
+    let coefficient = params[threshold_count];
+
+    let upper = if category < threshold_count {
+        params[category] - coefficient * predictor
+    } else {
+        f64::INFINITY
+    };
+    let lower = if category > 0 {
+        params[category - 1] - coefficient * predictor
+    } else {
+        f64::NEG_INFINITY
+    };
+
+    (upper, lower)
+}
+
+fn get_log_likelihood(params: &[f64], predictors: &[f64], category_indices: &[usize]) -> f64 {
+    predictors
+        .iter()
+        .zip(category_indices.iter())
+        .map(|(x, category)| {
+            let (upper, lower) = get_linear_bounds(params, *x, *category);
+            (logistic(upper) - logistic(lower)).ln()
+        })
+        .sum()
+}
+
+fn get_gradient(params: &[f64], predictors: &[f64], category_indices: &[usize]) -> DVector<f64> {
+    let threshold_count = params.len() - 1;
+    let mut gradient: DVector<f64> = DVector::zeros(params.len());
+
+    for (x, category) in predictors.iter().zip(category_indices.iter()) {
+        let (upper, lower) = get_linear_bounds(params, *x, *category);
+        let probability = logistic(upper) - logistic(lower);
+        let upper_density = if upper.is_finite() {
+            logistic_density(upper)
+        } else {
+            0f64
+        };
+        let lower_density = if lower.is_finite() {
+            logistic_density(lower)
+        } else {
+            0f64
+        };
+
+        if *category < threshold_count {
+            gradient[*category] += upper_density / probability;
+        }
+        if *category > 0 {
+            gradient[*category - 1] -= lower_density / probability;
+        }
+        gradient[threshold_count] -= x * (upper_density - lower_density) / probability;
+    }
+
+    gradient
+}
+
+/// Central-difference Hessian of the log-likelihood from the analytic gradient.
+fn get_hessian(params: &[f64], predictors: &[f64], category_indices: &[usize]) -> DMatrix<f64> {
+    let size = params.len();
+    let mut hessian: DMatrix<f64> = DMatrix::zeros(size, size);
+
+    for i in 0..size {
+        let step = HESSIAN_STEP * (1f64 + params[i].abs());
+        let mut forward = params.to_vec();
+        let mut backward = params.to_vec();
+        forward[i] += step;
+        backward[i] -= step;
+
+        let column = (get_gradient(&forward, predictors, category_indices)
+            - get_gradient(&backward, predictors, category_indices))
+            / (2f64 * step);
+        hessian.set_column(i, &column);
+    }
+
+    (&hessian + hessian.transpose()) / 2f64
+}
+
+fn are_thresholds_ordered(params: &[f64]) -> bool {
+    params[..params.len() - 1].windows(2).all(|w| w[0] < w[1])
+}
+
+/// Newton-Raphson with step halving. Returns the estimates and whether they converged.
+fn maximise_log_likelihood(
+    initial_params: Vec<f64>,
+    predictors: &[f64],
+    category_indices: &[usize],
+) -> (Vec<f64>, bool) {
+    let mut params = initial_params;
+    let mut log_likelihood = get_log_likelihood(&params, predictors, category_indices);
+
+    for _ in 0..vars::MAX_SOLVER_ITERATIONS {
+        let gradient = get_gradient(&params, predictors, category_indices);
+        let hessian = get_hessian(&params, predictors, category_indices);
+
+        let direction: DVector<f64> = match hessian.lu().solve(&gradient) {
+            Some(solution) => -solution,
+            None => return (params, false),
+        };
+
+        let mut step_size = 1f64;
+        let mut improved = false;
+
+        for _ in 0..MAX_STEP_HALVINGS {
+            let candidate: Vec<f64> = params
+                .iter()
+                .zip(direction.iter())
+                .map(|(p, d)| p + step_size * d)
+                .collect();
+            let candidate_log_likelihood =
+                get_log_likelihood(&candidate, predictors, category_indices);
+
+            if are_thresholds_ordered(&candidate)
+                && candidate_log_likelihood.is_finite()
+                && candidate_log_likelihood >= log_likelihood
+            {
+                params = candidate;
+                log_likelihood = candidate_log_likelihood;
+                improved = true;
+                break;
+            }
+
+            step_size /= 2f64;
+        }
+
+        let step_length = direction.amax() * step_size;
+
+        if !improved || step_length < vars::SOLVER_TOLERANCE {
+            return (
+                params,
+                step_length < vars::SOLVER_TOLERANCE || gradient.amax() < 1e-6,
+            );
+        }
+    }
+
+    (params, false)
+}
+
+/// Fits `logit P(z = 1) = intercept + slope * x` by Newton-Raphson and returns the
+/// estimates with the fitted probabilities.
+fn fit_binary_logistic(predictors: &[f64], outcomes: &[bool]) -> (Vector2<f64>, Vec<f64>) {
+    let mut params: Vector2<f64> = Vector2::zeros();
+
+    for _ in 0..vars::MAX_SOLVER_ITERATIONS {
+        let mut gradient: Vector2<f64> = Vector2::zeros();
+        let mut information: Matrix2<f64> = Matrix2::zeros();
+
+        for (x, z) in predictors.iter().zip(outcomes.iter()) {
+            let row = Vector2::new(1f64, *x);
+            let p = logistic(params.dot(&row));
+            gradient += row * ((*z as u8) as f64 - p);
+            information += row * row.transpose() * (p * (1f64 - p));
+        }
+
+        let step = match information.try_inverse() {
+            Some(inverse) => inverse * gradient,
+            None => break,
+        };
+
+        params += step;
+
+        if step.amax() < vars::SOLVER_TOLERANCE {
+            break;
+        }
+    }
+
+    let fitted: Vec<f64> = predictors
+        .iter()
+        .map(|x| logistic(params[0] + params[1] * x))
+        .collect();
+
+    (params, fitted)
+}
+
+/// Brant's Wald test of proportional odds: fits one binary logit per category boundary
+/// and tests whether the slopes are equal. Needs at least three categories.
+/// See: Brant, R. (1990). Assessing proportionality in the proportional odds model for
+/// ordinal logistic regression. Biometrics, 46(4), 1171-1178.
+fn get_brant_statistic(
+    predictors: &[f64],
+    category_indices: &[usize],
+    category_count: usize,
+) -> Option<f64> {
+    if category_count < 3 {
+        return None;
+    }
+
+    let boundary_count = category_count - 1;
+    let mut slopes: Vec<f64> = Vec::new();
+    let mut fitted: Vec<Vec<f64>> = Vec::new();
+    let mut information_inverses: Vec<Matrix2<f64>> = Vec::new();
+
+    for boundary in 0..boundary_count {
+        let outcomes: Vec<bool> = category_indices.iter().map(|c| *c > boundary).collect();
+        let (params, probabilities) = fit_binary_logistic(predictors, &outcomes);
+
+        let information: Matrix2<f64> = predictors
+            .iter()
+            .zip(probabilities.iter())
+            .map(|(x, p)| {
+                let row = Vector2::new(1f64, *x);
+                row * row.transpose() * (p * (1f64 - p))
+            })
+            .sum();
+
+        slopes.push(params[1]);
+        fitted.push(probabilities);
+        information_inverses.push(information.try_inverse()?);
+    }
+
+    let mut slope_covariance: DMatrix<f64> = DMatrix::zeros(boundary_count, boundary_count);
+
+    for j in 0..boundary_count {
+        for l in j..boundary_count {
+            let cross_information: Matrix2<f64> = predictors
+                .iter()
+                .enumerate()
+                .map(|(i, x)| {
+                    let row = Vector2::new(1f64, *x);
+                    row * row.transpose() * (fitted[l][i] * (1f64 - fitted[j][i]))
+                })
+                .sum();
+
+            let covariance = information_inverses[j] * cross_information * information_inverses[l];
+            slope_covariance[(j, l)] = covariance[(1, 1)];
+            slope_covariance[(l, j)] = covariance[(1, 1)];
+        }
+    }
+
+    let mut contrasts: DMatrix<f64> = DMatrix::zeros(boundary_count - 1, boundary_count);
+
+    for row in 0..boundary_count - 1 {
+        contrasts[(row, 0)] = 1f64;
+        contrasts[(row, row + 1)] = -1f64;
+    }
+
+    let differences = &contrasts * DVector::from_vec(slopes);
+    let difference_covariance = &contrasts * slope_covariance * contrasts.transpose();
+    let statistic = differences.transpose() * difference_covariance.try_inverse()? * &differences;
+
+    Some(statistic[(0, 0)].max(0f64))
+}
@@ -0,0 +1,202 @@
+use crate::{
+    fns::{
+        rank::{get_correlations, get_spearman_correlation},
+        score::get_score_columns,
+        stats::{
+            PEARSON_FISHER_VARIANCE_FACTOR, SPEARMAN_FISHER_VARIANCE_FACTOR, get_correlation_power,
+            get_required_sample_size,
+        },
+        util::round_value,
+    },
+    util::{
+        types::{
+            EffectSizePowerData, Feedback, PowerAnalysisData, PowerCurvePointData,
+            QuestionPowerData, ScoreColumns,
+        },
+        vars,
+    },
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+/// Power of each practice question to detect its observed correlation with quality, analytic
+/// for Pearson's r and Spearman's rho and simulated from the observed answers, with the sample
+/// sizes that reach `target_power` for the observed rho and for each of `effect_sizes`.
+pub fn calculate_power_analysis(
+    feedbacks: &[Feedback],
+    effect_sizes: &[f64],
+    target_power: f64,
+    simulation_count: usize,
+) -> PowerAnalysisData {
+    let columns: ScoreColumns = get_score_columns(feedbacks);
+    let respondent_count = columns.get_respondent_count();
+
+    let questions: Vec<QuestionPowerData> = get_correlations(&columns)
+        .into_iter()
+        .map(|correlation| {
+            let column = columns
+                .questions
+                .iter()
+                .find(|c| c.question == correlation.question)
+                .unwrap();
+            let (scores, qualities) = columns.get_applicable_scores(column);
+            let rho = correlation.rho;
+
+            let power_curve: Vec<PowerCurvePointData> = vars::POWER_CURVE_ANSWER_COUNTS
+                .iter()
+                .map(|n| PowerCurvePointData {
+                    answer_count: *n,
+                    power_pearson: round_value(get_correlation_power(
+                        rho,
+                        *n,
+                        PEARSON_FISHER_VARIANCE_FACTOR,
+                    )),
+                    power_spearman: round_value(get_correlation_power(
+                        rho,
+                        *n,
+                        SPEARMAN_FISHER_VARIANCE_FACTOR,
+                    )),
+                    power_simulated: round_value(get_simulated_power(
+                        &scores,
+                        &qualities,
+                        rho,
+                        *n,
+                        simulation_count,
+                    )),
+                })
+                .collect();
+
+            let required_answer_count_spearman =
+                get_required_sample_size(rho, target_power, SPEARMAN_FISHER_VARIANCE_FACTOR);
+
+            QuestionPowerData {
+                question: correlation.question,
+                question_info: correlation.question_info,
+                rho,
+                answer_count: correlation.answer_count,
+                respondent_count,
+                power: round_value(get_correlation_power(
+                    rho,
+                    correlation.answer_count,
+                    SPEARMAN_FISHER_VARIANCE_FACTOR,
+                )),
+                required_answer_count_pearson: get_required_sample_size(
+                    rho,
+                    target_power,
+                    PEARSON_FISHER_VARIANCE_FACTOR,
+                ),
+                required_answer_count_spearman,
+                required_answer_count_simulated: get_simulated_sample_size(
+                    &scores,
+                    &qualities,
+                    rho,
+                    target_power,
+                    simulation_count,
+                ),
+                required_respondent_count: required_answer_count_spearman.map(|n| {
+                    (n as f64 * respondent_count as f64 / correlation.answer_count as f64).ceil()
+                        as usize
+                }),
+                power_curve,
+            }
+        })
+        .collect();
+
+    let effect_sizes: Vec<EffectSizePowerData> = effect_sizes
+        .iter()
+        .map(|rho| {
+            let required_answer_count_spearman =
+                get_required_sample_size(*rho, target_power, SPEARMAN_FISHER_VARIANCE_FACTOR);
+
+            EffectSizePowerData {
+                rho: *rho,
+                required_answer_count_pearson: get_required_sample_size(
+                    *rho,
+                    target_power,
+                    PEARSON_FISHER_VARIANCE_FACTOR,
+                ),
+                required_answer_count_spearman,
+                powered_question_count: questions
+                    .iter()
+                    .filter(|q| required_answer_count_spearman.is_some_and(|n| q.answer_count >= n))
+                    .count(),
+            }
+        })
+        .collect();
+
+    PowerAnalysisData {
+        significance_level: vars::SIGNIFICANCE_LEVEL,
+        target_power,
+        simulation_count,
+        effect_sizes,
+        questions,
+    }
+}
+
+/// Smallest answer count whose simulated power reaches `target_power`: the upper bound
+/// doubles from the fewest answers with a p-value until it reaches the target, then the
+/// count is bisected. `None` when not even `MAX_SIMULATED_ANSWER_COUNT` answers reach it.
+pub fn get_simulated_sample_size(
+    scores: &[f64],
+    qualities: &[f64],
+    rho: f64,
+    target_power: f64,
+    simulation_count: usize,
+) -> Option<usize> {
+    let reaches_target =
+        |n: usize| get_simulated_power(scores, qualities, rho, n, simulation_count) >= target_power;
+
+    let mut lower = 3;
+    if reaches_target(lower) {
+        return Some(lower);
+    }
+
+    let mut upper = lower * 2;
+    while !reaches_target(upper) {
+        if upper >= vars::MAX_SIMULATED_ANSWER_COUNT {
+            return None;
+        }
+        lower = upper;
+        upper = usize::min(upper * 2, vars::MAX_SIMULATED_ANSWER_COUNT);
+    }
+
+    while upper - lower > 1 {
+        let middle = (lower + upper) / 2;
+        if reaches_target(middle) {
+            upper = middle;
+        } else {
+            lower = middle;
+        }
+    }
+    Some(upper)
+}
+
+/// Share of samples of `n` answers, drawn with replacement from the observed score and quality
+/// pairs, in which the one-sided test finds a significant correlation in the direction of
+/// `rho`. Every call uses the same seed, so the curve is smooth and reproducible.
+pub fn get_simulated_power(
+    scores: &[f64],
+    qualities: &[f64],
+    rho: f64,
+    n: usize,
+    simulation_count: usize,
+) -> f64 {
+    let mut rng = StdRng::seed_from_u64(vars::RANDOM_SEED);
+    let mut sample_scores: Vec<f64> = vec![0f64; n];
+    let mut sample_qualities: Vec<f64> = vec![0f64; n];
+    let mut significant_count = 0;
+
+    for _ in 0..simulation_count {
+        for i in 0..n {
+            let index = rng.random_range(0..scores.len());
+            sample_scores[i] = scores[index];
+            sample_qualities[i] = qualities[index];
+        }
+
+        let (sample_rho, p_value) = get_spearman_correlation(&sample_scores, &sample_qualities);
+        if p_value < vars::SIGNIFICANCE_LEVEL && sample_rho * rho.signum() > 0f64 {
+            significant_count += 1;
+        }
+    }
+
+    significant_count as f64 / simulation_count as f64
+}
@@ -0,0 +1,641 @@
+use crate::{
+    fns::util::get_answer_score,
+    util::{
+        types::{Feedback, Question, QuestionInfo, QuestionKind},
+        vars,
+    },
+};
+use std::sync::LazyLock;
+
+pub static QUESTION_CATEGORIES: [&str; 7] = [
+    "Documentation",
+    "Mutability",
+    "Code",
+    "Version control",
+    "Code review",
+    "Tooling",
+    "Testing",
+];
+
+struct QuestionDefinition {
+    id: &'static str,
+    field_name: &'static str,
+    text: &'static str,
+    impact_id: &'static str,
+    impact_field_name: &'static str,
+    impact_text: &'static str,
+    label: &'static str,
+    category: &'static str,
+    answers: &'static [&'static str],
+    unscored_answers: &'static [&'static str],
+}
+
+/// Answers of the practices asked as a plain yes or no, from the lowest to the highest score.
+static ANSWERS: [&str; 3] = ["No", "Yes, sometimes", "Yes"];
+/// Answers of the practices asked about how often they were followed.
+static FREQUENCY_ANSWERS: [&str; 3] = ["No", "Yes, sometimes", "Yes, always or often"];
+static UNSCORED_ANSWERS: [&str; 2] = ["Not applicable", "I don't know"];
+
+/// Practice questions in survey order, each defined once with its impact rating. IDs are
+/// stable across runs: `Pnn` for a practice and `Inn` for the impact rating of the same
+/// practice. Analyses iterate the registry built from this table.
+static QUESTIONS: [QuestionDefinition; 32] = [
+    QuestionDefinition {
+        id: "P01",
+        field_name: "were_requirements_documents_written",
+        text: "Were requirements documents written and maintained?",
+        impact_id: "I01",
+        impact_field_name: "impact_writing_requirements_documents",
+        impact_text: "Writing and maintaining requirements documents",
+        label: "Requirements documents",
+        category: "Documentation",
+        answers: &[
+            "No",
+            "Partially; they were written but not maintained",
+            "Yes, they were written and maintained",
+        ],
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P02",
+        field_name: "was_initial_project_plan_written",
+        text: "Was an initial project plan document written?",
+        impact_id: "I02",
+        impact_field_name: "impact_writing_initial_project_plan",
+        impact_text: "Writing an initial project plan document",
+        label: "Initial project plan",
+        category: "Documentation",
+        answers: &[
+            "No",
+            "Partially; it was written but not maintained",
+            "Yes, it was written and maintained",
+        ],
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P03",
+        field_name: "were_specification_documents_written",
+        text: "Were specification documents written and maintained?",
+        impact_id: "I03",
+        impact_field_name: "impact_writing_specification_documents",
+        impact_text: "Writing and maintaining specification documents",
+        label: "Specification documents",
+        category: "Documentation",
+        answers: &[
+            "No",
+            "Partially; they were written but not maintained",
+            "Yes, they were written and maintained",
+        ],
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P04",
+        field_name: "were_technical_design_documents_written",
+        text: "Were technical design documents written and maintained?",
+        impact_id: "I04",
+        impact_field_name: "impact_writing_technical_design_documents",
+        impact_text: "Writing and maintaining technical design documents",
+        label: "Technical design documents",
+        category: "Documentation",
+        answers: &[
+            "No",
+            "Partially; they were written but not maintained",
+            "Yes, they were written and maintained",
+        ],
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P05",
+        field_name: "was_sbom_document_written",
+        text: "Was a software bill of materials (SBOM) document written and maintained?",
+        impact_id: "I05",
+        impact_field_name: "impact_writing_sbom_document",
+        impact_text: "Writing and maintaining an SBOM document",
+        label: "SBOM document",
+        category: "Documentation",
+        answers: &[
+            "No",
+            "Partially; it was written but not maintained",
+            "Yes, it was written and maintained",
+        ],
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P06",
+        field_name: "were_draft_design_documents_written",
+        text: "Were draft design documents written?",
+        impact_id: "I06",
+        impact_field_name: "impact_writing_draft_design_documents",
+        impact_text: "Writing draft design documents",
+        label: "Draft design documents",
+        category: "Documentation",
+        answers: &ANSWERS,
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P07",
+        field_name: "were_project_documents_reviewed",
+        text: "Were project documents reviewed by someone, not including the author?",
+        impact_id: "I07",
+        impact_field_name: "impact_requiring_document_reviewers",
+        impact_text: "Requiring at least one reviewer for documents",
+        label: "Document reviews",
+        category: "Documentation",
+        answers: &[
+            "No",
+            "Partially; some documents were reviewed",
+            "Yes, all or most documents were reviewed",
+        ],
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P08",
+        field_name: "were_mutability_and_side_effects_avoided",
+        text: "Were mutability and side effects avoided?",
+        impact_id: "I08",
+        impact_field_name: "impact_avoiding_mutability",
+        impact_text: "Avoiding mutability and side effects",
+        label: "Avoiding mutability",
+        category: "Mutability",
+        answers: &[
+            "No, they were purposefully not avoided or this practice was not considered",
+            "Yes, sometimes",
+            "Yes",
+        ],
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P09",
+        field_name: "were_mutable_names_encoded",
+        text: "Were mutable variable and method names lexically encoded?",
+        impact_id: "I09",
+        impact_field_name: "impact_encoding_mutable_names",
+        impact_text: "Lexically encoding mutable variable and method names",
+        label: "Encoding mutable names",
+        category: "Mutability",
+        answers: &ANSWERS,
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P10",
+        field_name: "were_nulls_avoided",
+        text: "Was the use of `null` values avoided?",
+        impact_id: "I10",
+        impact_field_name: "impact_nulls",
+        impact_text: "Avoiding `null` values",
+        label: "Avoiding nulls",
+        category: "Code",
+        answers: &ANSWERS,
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P11",
+        field_name: "was_non_english_used",
+        text: "Were languages other than English used in written technical disciplines?",
+        impact_id: "I11",
+        impact_field_name: "impact_using_english",
+        impact_text: "Primarily using English in written technical disciplines",
+        label: "Using English",
+        category: "Code",
+        answers: &[
+            "Yes, another language was used a lot",
+            "Yes, another language was used to some degree",
+            "No, mostly English was used",
+        ],
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P12",
+        field_name: "was_code_style_enforced",
+        text: "Was a code style established and enforced?",
+        impact_id: "I12",
+        impact_field_name: "impact_code_style",
+        impact_text: "Establishing and enforcing code style",
+        label: "Code style",
+        category: "Code",
+        answers: &[
+            "No",
+            "Partially; established but not enforced",
+            "Yes, established and enforced",
+        ],
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P13",
+        field_name: "was_code_refactored",
+        text: "Was existing code refactored during feature development?",
+        impact_id: "I13",
+        impact_field_name: "impact_code_refactoring",
+        impact_text: "Refactoring existing code during feature development",
+        label: "Refactoring",
+        category: "Code",
+        answers: &[
+            "No",
+            "Yes, without separate branches",
+            "Yes, with major refactoring done in separate branches",
+        ],
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P14",
+        field_name: "were_posix_timestamps_used",
+        text: "Were POSIX timestamps used for point-in-time variables?",
+        impact_id: "I14",
+        impact_field_name: "impact_posix_timestamps",
+        impact_text: "Using POSIX timestamps for point-in-time variables",
+        label: "POSIX timestamps",
+        category: "Code",
+        answers: &FREQUENCY_ANSWERS,
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P15",
+        field_name: "were_magic_numbers_replaced",
+        text: "Were variables created for \"magic numbers\" ?",
+        impact_id: "I15",
+        impact_field_name: "impact_magic_numbers",
+        impact_text: "Creating variables for magic numbers",
+        label: "Replacing magic numbers",
+        category: "Code",
+        answers: &[
+            "No, \"magic numbers\" were used as literal values",
+            "Yes, sometimes",
+            "Yes, variables with meaningful names were created",
+        ],
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P16",
+        field_name: "were_critical_code_commented",
+        text: "Were code comments used to explain critical code?",
+        impact_id: "I16",
+        impact_field_name: "impact_code_comments",
+        impact_text: "Using code comments to explain critical code",
+        label: "Commenting critical code",
+        category: "Code",
+        answers: &FREQUENCY_ANSWERS,
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P17",
+        field_name: "were_regex_patterns_commented",
+        text: "Were Regex patterns explained with code comments?",
+        impact_id: "I17",
+        impact_field_name: "impact_regex_comments",
+        impact_text: "Explaining Regex patterns with code comments",
+        label: "Commenting regex patterns",
+        category: "Code",
+        answers: &FREQUENCY_ANSWERS,
+        unscored_answers: &["Regex patterns were not used", "I don't know"],
+    },
+    QuestionDefinition {
+        id: "P18",
+        field_name: "were_todo_comments_avoided",
+        text: "Was leaving `TODO` code comments avoided?",
+        impact_id: "I18",
+        impact_field_name: "impact_avoiding_todo_comments",
+        impact_text: "Avoiding leaving `TODO` code comments",
+        label: "Avoiding TODO comments",
+        category: "Code",
+        answers: &FREQUENCY_ANSWERS,
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P19",
+        field_name: "was_ai_generated_code_reviewed",
+        text: "Was code generated by AI tools reviewed exceptionally carefully?",
+        impact_id: "I19",
+        impact_field_name: "impact_reviewing_ai_code",
+        impact_text: "Reviewing code generated by AI tools exceptionally carefully",
+        label: "Reviewing AI code",
+        category: "Code",
+        answers: &FREQUENCY_ANSWERS,
+        unscored_answers: &[
+            "AI tools were not used to generate code",
+            "I don't know (e.g. you don't know how your team members use generative AI tools)",
+        ],
+    },
+    QuestionDefinition {
+        id: "P20",
+        field_name: "was_branching_strategy_established",
+        text: "Was a branching strategy established?",
+        impact_id: "I20",
+        impact_field_name: "impact_branching_strategy",
+        impact_text: "Establishing a branching strategy",
+        label: "Branching strategy",
+        category: "Version control",
+        answers: &ANSWERS,
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P21",
+        field_name: "was_branch_naming_strategy_established",
+        text: "Was a branch naming strategy established?",
+        impact_id: "I21",
+        impact_field_name: "impact_branch_naming_strategy",
+        impact_text: "Establishing a branch naming strategy",
+        label: "Branch naming strategy",
+        category: "Version control",
+        answers: &ANSWERS,
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P22",
+        field_name: "was_commit_message_format_established",
+        text: "Was a commit message format established?",
+        impact_id: "I22",
+        impact_field_name: "impact_commit_message_format",
+        impact_text: "Establishing a commit message format",
+        label: "Commit message format",
+        category: "Version control",
+        answers: &ANSWERS,
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P23",
+        field_name: "was_merging_strategy_established",
+        text: "Was a merging strategy established?",
+        impact_id: "I23",
+        impact_field_name: "impact_merging_strategy",
+        impact_text: "Establishing a merging strategy",
+        label: "Merging strategy",
+        category: "Version control",
+        answers: &ANSWERS,
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P24",
+        field_name: "were_higher_level_design_issues_considered",
+        text: "Were higher level design issues considered in code reviews?",
+        impact_id: "I24",
+        impact_field_name: "impact_higher_level_design",
+        impact_text: "Considering higher level design issues in code reviews",
+        label: "Design issues in reviews",
+        category: "Code review",
+        answers: &FREQUENCY_ANSWERS,
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P25",
+        field_name: "was_reviewer_required",
+        text: "Was at least one reviewer required for all code changes?",
+        impact_id: "I25",
+        impact_field_name: "impact_requiring_reviewer",
+        impact_text: "Requiring at least one reviewer for all code changes",
+        label: "Required code reviewer",
+        category: "Code review",
+        answers: &ANSWERS,
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P26",
+        field_name: "were_formatter_and_linter_tools_established",
+        text: "Were formatter and linter tools established?",
+        impact_id: "I26",
+        impact_field_name: "impact_formatter_linter_tools",
+        impact_text: "Establishing formatter and linter tools",
+        label: "Formatter and linter tools",
+        category: "Tooling",
+        answers: &["No", "Yes, some tools", "Yes, all or most tools"],
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P27",
+        field_name: "were_static_analysis_tools_established",
+        text: "Were static analysis tools established?",
+        impact_id: "I27",
+        impact_field_name: "impact_static_analysis_tools",
+        impact_text: "Establishing static analysis tools",
+        label: "Static analysis tools",
+        category: "Tooling",
+        answers: &["No", "Yes, some tools", "Yes, all or most tools"],
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P28",
+        field_name: "were_semi_automated_tools_configured",
+        text: "Were configurations for semi-automated tools centralised?",
+        impact_id: "I28",
+        impact_field_name: "impact_centralising_tools",
+        impact_text: "Centralising configurations of tools",
+        label: "Centralised tool configuration",
+        category: "Tooling",
+        answers: &[
+            "No",
+            "Yes, some configurations were centralised",
+            "Yes, all or most configurations were centralised",
+        ],
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P29",
+        field_name: "were_semi_automated_processes_run",
+        text: "Were semi-automated processes run as part of CI/CD regularly?",
+        impact_id: "I29",
+        impact_field_name: "impact_semi_automated_ci_cd",
+        impact_text: "Running semi-automatic tools as part of CI/CD",
+        label: "Semi-automated CI/CD",
+        category: "Tooling",
+        answers: &FREQUENCY_ANSWERS,
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P30",
+        field_name: "were_automated_tests_run",
+        text: "Were automated tests run periodically?",
+        impact_id: "I30",
+        impact_field_name: "impact_automated_tests",
+        impact_text: "Running automated tests periodically",
+        label: "Automated tests",
+        category: "Testing",
+        answers: &[
+            "No",
+            "Partially; some tests were run periodically",
+            "Yes, all or most tests were run periodically",
+        ],
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P31",
+        field_name: "were_tests_written_like_production_code",
+        text: "Were tests written like production code?",
+        impact_id: "I31",
+        impact_field_name: "impact_tests_like_production",
+        impact_text: "Writing tests like production code",
+        label: "Tests like production code",
+        category: "Testing",
+        answers: &ANSWERS,
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+    QuestionDefinition {
+        id: "P32",
+        field_name: "were_unit_tests_written_with_boc",
+        text: "Were unit tests written with the build-operate-check pattern?",
+        impact_id: "I32",
+        impact_field_name: "impact_unit_tests_boc",
+        impact_text: "Writing unit tests with the build-operate-check pattern",
+        label: "Build-operate-check unit tests",
+        category: "Testing",
+        answers: &ANSWERS,
+        unscored_answers: &UNSCORED_ANSWERS,
+    },
+];
+
+static OUTCOME: Question = Question {
+    id: "Q01",
+    field_name: "project_quality_estimate",
+    kind: QuestionKind::Outcome,
+    text: "Estimate the level of quality of your chosen project",
+    label: "Quality estimate",
+    category: "",
+    pair: None,
+    answers: &[],
+    unscored_answers: &[],
+};
+
+/// Every practice, then every impact rating, each in survey order, then the outcome.
+static REGISTRY: LazyLock<Vec<Question>> = LazyLock::new(|| {
+    let practices = QUESTIONS.iter().map(|q| Question {
+        id: q.id,
+        field_name: q.field_name,
+        kind: QuestionKind::Practice,
+        text: q.text,
+        label: q.label,
+        category: q.category,
+        pair: Some(q.impact_field_name),
+        answers: q.answers,
+        unscored_answers: q.unscored_answers,
+    });
+    let impacts = QUESTIONS.iter().map(|q| Question {
+        id: q.impact_id,
+        field_name: q.impact_field_name,
+        kind: QuestionKind::Impact,
+        text: q.impact_text,
+        label: q.label,
+        category: q.category,
+        pair: Some(q.field_name),
+        answers: &[],
+        unscored_answers: &[],
+    });
+
+    let registry: Vec<Question> = practices.chain(impacts).chain([OUTCOME.clone()]).collect();
+
+    if let Err(err) = validate_registry(&registry) {
+        panic!("Invalid question registry: {}", err);
+    }
+    registry
+});
+
+/// Returns every registered question.
+pub fn get_questions() -> &'static [Question] {
+    &REGISTRY
+}
+
+/// Returns the questions of one kind in survey order.
+pub fn get_questions_of_kind(kind: QuestionKind) -> impl Iterator<Item = &'static Question> {
+    REGISTRY.iter().filter(move |q| q.kind == kind)
+}
+
+/// Returns the practice or impact question of a field name.
+pub fn get_question(field_name: &str) -> Option<&'static Question> {
+    REGISTRY.iter().find(|q| q.field_name == field_name)
+}
+
+/// Returns the practice question of an ID or field name.
+pub fn get_practice(key: &str) -> Option<&'static Question> {
+    get_questions_of_kind(QuestionKind::Practice).find(|q| q.id == key || q.field_name == key)
+}
+
+/// Returns the impact rating field of a practice, or the practice field of an impact rating.
+pub fn get_paired_field_name(field_name: &str) -> Option<&'static str> {
+    get_question(field_name).and_then(|q| q.pair)
+}
+
+/// Checks that every question maps to a field of a parsed response, so a misspelt field
+/// name fails when the CSV is loaded rather than silently dropping a question.
+pub fn validate_question_fields(feedback: &Feedback) -> Result<(), String> {
+    match get_questions()
+        .iter()
+        .filter(|q| q.kind != QuestionKind::Outcome)
+        .find(|q| feedback.get_answer(q.field_name).is_none())
+    {
+        Some(question) => Err(format!(
+            "Question {} has no field {}",
+            question.id, question.field_name
+        )),
+        None => Ok(()),
+    }
+}
+
+/// IDs and field names must be unique and every pairing must point back.
+pub fn validate_registry(registry: &[Question]) -> Result<(), String> {
+    for (i, question) in registry.iter().enumerate() {
+        if registry[..i]
+            .iter()
+            .any(|q| q.id == question.id || q.field_name == question.field_name)
+        {
+            return Err(format!("duplicate question {}", question.id));
+        }
+
+        let expected_pair_kind = match question.kind {
+            QuestionKind::Practice => QuestionKind::Impact,
+            QuestionKind::Impact => QuestionKind::Practice,
+            QuestionKind::Outcome => continue,
+        };
+        let is_paired = registry.iter().any(|q| {
+            Some(q.field_name) == question.pair
+                && q.kind == expected_pair_kind
+                && q.pair == Some(question.field_name)
+        });
+
+        if !is_paired {
+            return Err(format!("{} has no matching pair", question.id));
+        }
+    }
+
+    Ok(())
+}
+
+impl Question {
+    /// Scores the answer of a response: the answer weighting of a practice, the rating of an
+    /// impact or the quality estimate. `None` when a practice is not applicable or an impact
+    /// was not rated.
+    pub fn get_score(&self, feedback: &Feedback) -> Option<f64> {
+        match self.kind {
+            QuestionKind::Practice => {
+                let score = get_answer_score(feedback.get_answer(self.field_name)?);
+                (score != vars::WEIGHTING_NOT_APPLICABLE).then_some(score as f64)
+            }
+            QuestionKind::Impact => feedback.get_answer(self.field_name)?.parse::<f64>().ok(),
+            QuestionKind::Outcome => Some(feedback.project_quality_estimate as f64),
+        }
+    }
+}
+
+/// Returns the ID, wording, label and category of a practice or impact field;
+/// `None` for derived rows such as factors or composites.
+pub fn get_question_info(field_name: &str) -> Option<QuestionInfo> {
+    get_question(field_name)
+        .filter(|q| q.kind != QuestionKind::Outcome)
+        .map(|q| QuestionInfo {
+            question_id: q.id.to_owned(),
+            question_text: q.text.to_owned(),
+            question_label: q.label.to_owned(),
+            question_category: q.category.to_owned(),
+        })
+}
+
+/// Returns the survey wording of a practice question or impact rating.
+pub fn get_question_text(field_name: &str) -> &str {
+    get_question(field_name).map_or(field_name, |q| q.text)
+}
+
+/// Returns the survey section a practice question or impact rating belongs to.
+pub fn get_question_category(field_name: &str) -> &str {
+    get_question(field_name).map_or("", |q| q.category)
+}
+
+/// Returns the short display label of a practice question or impact rating.
+pub fn get_question_label(field_name: &str) -> &str {
+    get_question(field_name).map_or(field_name, |q| q.label)
+}
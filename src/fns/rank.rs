@@ -1,612 +1,226 @@
 use crate::{
-    fns::util::get_answer_score,
-    util::{
-        types::{Feedback, FeedbackWithData, QuestionCorrelationData},
-        vars,
+    fns::{
+        imputation::get_pooled_correlations,
+        question::{get_practice, get_question_info},
+        ranking::{get_ranking, get_tie_sum},
+        score::get_score_columns,
+        stats::{get_adjusted_p_values, get_correlation_confidence_interval},
+        util::round_value,
+    },
+    util::types::{
+        Feedback, MissingDataStrategy, Question, QuestionCorrelationData, Ranking, ScoreColumns,
+        SkipReason, SkippedQuestionData, TieMethod,
     },
 };
 use statrs::distribution::{ContinuousCDF, StudentsT};
 
-static IS_TRACE: bool = false;
-static IS_DEBUG: bool = false;
-
+#[allow(clippy::ptr_arg)]
 pub fn calculate_rankings(feedbacks: &Vec<Feedback>) -> Vec<QuestionCorrelationData> {
-    let mut result = get_feedbacks_with_ranks(feedbacks);
+    let mut result = get_correlations(&get_score_columns(feedbacks));
+    adjust_p_values(&mut result);
     result.sort_by(|a, b| b.rho.partial_cmp(&a.rho).unwrap());
-    return result;
+    result
 }
 
-macro_rules! parse_score {
-    ( $feedback:expr, $feedback_with_data:expr, $(($field:ident, $score_field:ident)),* ) => {
-        $(
-              let score = get_answer_score($feedback.$field.as_str());
-              $feedback_with_data.$score_field = score;
-        )*
-    };
-}
+/// Rankings with the respondents of each correlation chosen by `strategy`, and the practice
+/// questions left without a correlation. Listwise deletion keeps the respondents who answered
+/// every listed question, so the listed questions share their respondents, while the others
+/// also drop their own unscored answers.
+pub fn calculate_strategy_rankings(
+    feedbacks: &Vec<Feedback>,
+    strategy: &MissingDataStrategy,
+) -> Result<(Vec<QuestionCorrelationData>, Vec<SkippedQuestionData>), String> {
+    let (columns, rankings) = match strategy {
+        MissingDataStrategy::Pairwise => {
+            (get_score_columns(feedbacks), calculate_rankings(feedbacks))
+        }
+        MissingDataStrategy::Listwise { questions } => {
+            if questions.is_empty() {
+                return Err("Listwise deletion needs at least one question".to_owned());
+            }
 
-// NOTE filters out zeroes even with quality scores
-macro_rules! parse_ranks {
-    ( $feedbacks:expr, $(($( $score_field:ident ).+, $rank_field:ident)),* ) => {
-        $(
-            let mut applicable_feedbacks: Vec<_> = $feedbacks
-                .iter_mut()
-                .filter(|f| f.$($score_field).+ != vars::WEIGHTING_NOT_APPLICABLE)
+            let questions: Vec<&Question> = questions
+                .iter()
+                .map(|key| {
+                    get_practice(key).ok_or_else(|| format!("Unknown practice question: {}", key))
+                })
+                .collect::<Result<_, _>>()?;
+            let complete: Vec<Feedback> = feedbacks
+                .iter()
+                .filter(|f| questions.iter().all(|q| q.get_score(f).is_some()))
+                .cloned()
                 .collect();
 
-            applicable_feedbacks.sort_by(|a, b| b.$($score_field).+.partial_cmp(&a.$($score_field).+).unwrap());
-
-            let mut last_score: i8 = -10;
-            let mut rank_sum: f64 = 0.0;
-            let mut tie_count: usize = 0;
-
-            for i in 0..applicable_feedbacks.len() {
-                let current_rank = (i + 1) as f64;
-                let feedback = &mut applicable_feedbacks[i];
-                let score = feedback.$($score_field).+;
-
-                if score == last_score {
-                    rank_sum += current_rank;
-                    tie_count += 1;
-                } else {
-                    if tie_count > 0 {
-                        let average_rank = rank_sum / tie_count as f64;
-                        for j in (i - tie_count)..i {
-                            applicable_feedbacks[j].$rank_field = average_rank;
-                        }
-                    }
-                    last_score = score;
-                    rank_sum = current_rank;
-                    tie_count = 1;
-                }
-
-                if i == applicable_feedbacks.len() - 1 {
-                    let average_rank = rank_sum / tie_count as f64;
-                    for j in (i + 1 - tie_count)..=i {
-                        applicable_feedbacks[j].$rank_field = average_rank;
-                    }
-                }
+            if complete.len() < 3 {
+                return Err(format!(
+                    "Only {} respondents answered every listwise question",
+                    complete.len()
+                ));
             }
 
-            if IS_DEBUG {
-                println!(
-                    "Scores sorted by {}: {:#?}",
-                    stringify!($($score_field).+),
-                    applicable_feedbacks.iter()
-                        .map(|f| (
-                            f.$($score_field).+,
-                            f.$rank_field
-                        ))
-                        .collect::<Vec<_>>()
-                );
-            }
-        )*
-
-        if IS_TRACE {
-            println!("Feedbacks: {:#?}", $feedbacks);
+            (get_score_columns(&complete), calculate_rankings(&complete))
         }
-    };
-}
-
-// NOTE filters out zeroes even with quality scores
-macro_rules! parse_correlations {
-    (
-        $feedbacks_with_data:expr, $results:expr,
-        $(( $question_name:ident, $( $score_field:ident ).+, $rank_field:ident )),*
-    ) => {
-
-        if IS_DEBUG {
-            println!("Total answers: {}", $feedbacks_with_data.len());
+        MissingDataStrategy::MultipleImputation {
+            imputation_count,
+            iteration_count,
+        } => {
+            let columns = get_score_columns(feedbacks);
+            let mut result = get_pooled_correlations(&columns, *imputation_count, *iteration_count);
+            adjust_p_values(&mut result);
+            result.sort_by(|a, b| b.rho.partial_cmp(&a.rho).unwrap());
+            (columns, result)
         }
+    };
 
-        $(
-              let mut applicable_feedbacks: Vec<&mut FeedbackWithData> = $feedbacks_with_data
-                  .iter_mut()
-                  .filter(|f| f.$($score_field).+ != vars::WEIGHTING_NOT_APPLICABLE)
-                  .collect();
-
-              let applicable_answer_count = applicable_feedbacks.len();
-
-              if applicable_answer_count < 3 {
-                  panic!("Not enough applicable answers for correlation calculation: {}", applicable_answer_count);
-              }
-
-              // Parse ranks for the applicable feedbacks; ignore the rest in ranking
-              parse_ranks!(
-                  applicable_feedbacks,
-                  (
-                      feedback.project_quality_estimate,
-                      project_quality_estimate_rank
-                  )
-              );
-
-              if IS_DEBUG {
-                println!("### Question: {}", stringify!($question_name));
-                println!("Applicable answer count: {}", applicable_answer_count);
-              }
-
-              let mean_rank_d = applicable_feedbacks
-                  .iter()
-                  .map(|f| f.$rank_field)
-                  .sum::<f64>() / applicable_answer_count as f64;
-
-              let mean_rank_q = applicable_feedbacks
-                  .iter()
-                  .map(|f| f.project_quality_estimate_rank)
-                  .sum::<f64>() / applicable_answer_count as f64;
-
-              if IS_DEBUG {
-                  println!("Mean rank Q: {:#.05} | Mean rank Quality: {:#.05}",
-                    mean_rank_d,
-                    mean_rank_q
-                  );
-              }
-
-              let mut rank_m_product_sum = 0f64;
-              let mut rank_d_m_sq_sum = 0f64;
-              let mut rank_q_m_sq_sum = 0f64;
-
-              for feedback in applicable_feedbacks {
-                  let rank_d = feedback.$rank_field;
-                  let rank_q = feedback.project_quality_estimate_rank;
-                  let rank_d_m = rank_d - mean_rank_d;
-                  let rank_q_m = rank_q - mean_rank_q;
-                  let rank_m_product = rank_d_m * rank_q_m;
-                  let rank_d_m_sq = f64::powf(rank_d_m, 2f64);
-                  let rank_q_m_sq = f64::powf(rank_q_m, 2f64);
-
-                  rank_m_product_sum += rank_m_product;
-                  rank_d_m_sq_sum += rank_d_m_sq;
-                  rank_q_m_sq_sum += rank_q_m_sq;
+    let skipped_questions = get_skipped_questions(&columns, &rankings);
+    Ok((rankings, skipped_questions))
+}
 
-                  if IS_DEBUG {
-                      println!(
-                          "Rank Q: {:#.05} | Rank Quality: {:#.05} | Rank Q - Mean Rank Q: {:#.05} | Rank Quality - Mean Rank Quality: {:#.05} | Rank - Mean Rank Product: {:#.05}",
-                          rank_d,
-                          rank_q,
-                          rank_d_m,
-                          rank_q_m,
-                          rank_m_product
-                    );
-                  }
-              }
+/// The practice questions of `columns` without a row in `correlations`, with the reason.
+pub fn get_skipped_questions(
+    columns: &ScoreColumns,
+    correlations: &[QuestionCorrelationData],
+) -> Vec<SkippedQuestionData> {
+    columns
+        .questions
+        .iter()
+        .filter(|column| !correlations.iter().any(|c| c.question == column.question))
+        .map(|column| {
+            let answer_count = columns.get_applicable_scores(column).0.len();
+
+            SkippedQuestionData {
+                question: column.question.to_owned(),
+                question_info: get_question_info(column.question),
+                answer_count,
+                reason: if answer_count < 3 {
+                    SkipReason::TooFewAnswers
+                } else {
+                    SkipReason::NoVariation
+                },
+            }
+        })
+        .collect()
+}
 
-            // See: https://en.wikipedia.org/wiki/Pearson_correlation_coefficient
-            let rho = rank_m_product_sum / (f64::sqrt(rank_d_m_sq_sum) * f64::sqrt(rank_q_m_sq_sum));
+/// Replaces `p_value_adjusted` with the Benjamini-Hochberg adjustment over all given rows.
+pub fn adjust_p_values(correlations: &mut [QuestionCorrelationData]) {
+    let p_values: Vec<f64> = correlations.iter().map(|c| c.p_value).collect();
+    for (correlation, p_value_adjusted) in correlations
+        .iter_mut()
+        .zip(get_adjusted_p_values(&p_values))
+    {
+        correlation.p_value_adjusted = round_value(p_value_adjusted);
+    }
+}
 
-            if rho < -1f64 || rho > 1f64 {
-                panic!("Spearman's rho is out of bounds: {:#.05}", rho);
+/// Spearman's rho of every practice question with the quality estimate, over the
+/// respondents with an applicable answer, in question order.
+pub fn get_correlations(columns: &ScoreColumns) -> Vec<QuestionCorrelationData> {
+    columns
+        .questions
+        .iter()
+        .filter_map(|column| {
+            let (scores, qualities) = columns.get_applicable_scores(column);
+            let applicable_answer_count = scores.len();
+
+            // Too few answers for a correlation, e.g. within a small subgroup
+            if applicable_answer_count < 3 {
+                return None;
             }
 
-            let t_test = rho * f64::sqrt((applicable_answer_count - 2) as f64 / (1f64 - f64::powf(rho, 2f64)));
-            let p_value = get_p_value(t_test, applicable_answer_count);
+            let (rho, p_value) = get_rank_correlation(
+                &column.get_applicable_ranking(),
+                &get_ranking(&qualities, TieMethod::Average),
+            );
 
-            if p_value < 0f64 || p_value > 1f64 {
-                panic!("p-value is out of bounds: {:#.05}", p_value);
+            // Undefined when every applicable answer has the same score or quality
+            if rho.is_nan() {
+                return None;
             }
 
-            if IS_DEBUG {
-                println!("Spearman's rho: {:#.05} | p-value: {:#.05} | t-test: {:#.05}",
-                  rho,
-                  p_value,
-                  t_test,
-                );
+            if !(0f64..=1f64).contains(&p_value) {
+                panic!("p-value is out of bounds: {:#.05}", p_value);
             }
 
-            let rho_rounded = (rho * vars::ROUND_DECIMAL_PLACES_MULTIPLIER as f64).round() / vars::ROUND_DECIMAL_PLACES_MULTIPLIER as f64;
-            let p_value_rounded = (p_value * vars::ROUND_DECIMAL_PLACES_MULTIPLIER as f64).round() / vars::ROUND_DECIMAL_PLACES_MULTIPLIER as f64;
-
-            $results.push(
-                QuestionCorrelationData {
-                    question: stringify!($question_name).to_owned(),
-                    rho: rho_rounded,
-                    p_value: p_value_rounded,
-                    answer_count: applicable_answer_count,
-                }
-            );
-        )*
-    };
+            let (rho_ci_lower, rho_ci_upper) =
+                get_correlation_confidence_interval(rho, applicable_answer_count);
+
+            Some(QuestionCorrelationData {
+                question: column.question.to_owned(),
+                question_info: get_question_info(column.question),
+                rho: round_value(rho),
+                rho_ci_lower: round_value(rho_ci_lower),
+                rho_ci_upper: round_value(rho_ci_upper),
+                p_value: round_value(p_value),
+                p_value_adjusted: round_value(p_value),
+                answer_count: applicable_answer_count,
+                imputed_answer_count: None,
+            })
+        })
+        .collect()
 }
 
-pub fn get_feedbacks_with_ranks(feedbacks: &Vec<Feedback>) -> Vec<QuestionCorrelationData> {
-    let mut feedbacks_with_data: Vec<FeedbackWithData> = Vec::new();
-
-    for feedback in feedbacks {
-        let mut feedback_with_data: FeedbackWithData = FeedbackWithData::new(feedback.to_owned());
+/// Spearman's rho with its p-value.
+pub fn get_spearman_correlation(x: &[f64], y: &[f64]) -> (f64, f64) {
+    get_rank_correlation(
+        &get_ranking(x, TieMethod::Average),
+        &get_ranking(y, TieMethod::Average),
+    )
+}
 
-        parse_score!(
-            &feedback,
-            &mut feedback_with_data,
-            (
-                were_requirements_documents_written,
-                were_requirements_documents_written_score
-            ),
-            (
-                was_initial_project_plan_written,
-                was_initial_project_plan_written_score
-            ),
-            (
-                were_specification_documents_written,
-                were_specification_documents_written_score
-            ),
-            (
-                were_technical_design_documents_written,
-                were_technical_design_documents_written_score
-            ),
-            (was_sbom_document_written, was_sbom_document_written_score),
-            (
-                were_draft_design_documents_written,
-                were_draft_design_documents_written_score
-            ),
-            (
-                were_project_documents_reviewed,
-                were_project_documents_reviewed_score
-            ),
-            (
-                were_mutability_and_side_effects_avoided,
-                were_mutability_and_side_effects_avoided_score
-            ),
-            (were_mutable_names_encoded, were_mutable_names_encoded_score),
-            (were_nulls_avoided, were_nulls_avoided_score),
-            (was_non_english_used, was_non_english_used_score),
-            (was_code_style_enforced, was_code_style_enforced_score),
-            (was_code_refactored, was_code_refactored_score),
-            (were_posix_timestamps_used, were_posix_timestamps_used_score),
-            (
-                were_magic_numbers_replaced,
-                were_magic_numbers_replaced_score
-            ),
-            (
-                were_critical_code_commented,
-                were_critical_code_commented_score
-            ),
-            (
-                were_regex_patterns_commented,
-                were_regex_patterns_commented_score
-            ),
-            (were_todo_comments_avoided, were_todo_comments_avoided_score),
-            (
-                was_ai_generated_code_reviewed,
-                was_ai_generated_code_reviewed_score
-            ),
-            (
-                was_branching_strategy_established,
-                was_branching_strategy_established_score
-            ),
-            (
-                was_branch_naming_strategy_established,
-                was_branch_naming_strategy_established_score
-            ),
-            (
-                was_commit_message_format_established,
-                was_commit_message_format_established_score
-            ),
-            (
-                was_merging_strategy_established,
-                was_merging_strategy_established_score
-            ),
-            (
-                were_higher_level_design_issues_considered,
-                were_higher_level_design_issues_considered_score
-            ),
-            (was_reviewer_required, was_reviewer_required_score),
-            (
-                were_formatter_and_linter_tools_established,
-                were_formatter_and_linter_tools_established_score
-            ),
-            (
-                were_static_analysis_tools_established,
-                were_static_analysis_tools_established_score
-            ),
-            (
-                were_semi_automated_tools_configured,
-                were_semi_automated_tools_configured_score
-            ),
-            (
-                were_semi_automated_processes_run,
-                were_semi_automated_processes_run_score
-            ),
-            (were_automated_tests_run, were_automated_tests_run_score),
-            (
-                were_tests_written_like_production_code,
-                were_tests_written_like_production_code_score
-            ),
-            (
-                were_unit_tests_written_with_boc,
-                were_unit_tests_written_with_boc_score
-            )
-        );
+/// Spearman's rho of two average rankings with its p-value. The rank sums of squares are
+/// reduced by the tie groups, which makes rho equal to the Pearson correlation of the
+/// fractional ranks.
+pub fn get_rank_correlation(ranking_x: &Ranking, ranking_y: &Ranking) -> (f64, f64) {
+    let count = ranking_x.ranks.len();
+    let n = count as f64;
+
+    let untied_sq_sum = (f64::powf(n, 3f64) - n) / 12f64;
+    let x_sq_sum = untied_sq_sum - get_tie_sum(&ranking_x.tie_sizes) / 12f64;
+    let y_sq_sum = untied_sq_sum - get_tie_sum(&ranking_y.tie_sizes) / 12f64;
+    let d_sq_sum: f64 = ranking_x
+        .ranks
+        .iter()
+        .zip(ranking_y.ranks.iter())
+        .map(|(a, b)| f64::powf(a - b, 2f64))
+        .sum();
+
+    let rho = ((x_sq_sum + y_sq_sum - d_sq_sum) / (2f64 * f64::sqrt(x_sq_sum * y_sq_sum)))
+        .clamp(-1f64, 1f64);
+
+    // Undefined without variation in either variable
+    if rho.is_nan() {
+        return (rho, f64::NAN);
+    }
 
-        feedbacks_with_data.push(feedback_with_data);
+    // The t statistic is infinite for perfectly monotonic ranks
+    if rho.abs() == 1f64 {
+        return (rho, 0f64);
     }
 
-    let mut results: Vec<QuestionCorrelationData> = Vec::new();
+    let t_test = rho * f64::sqrt((count - 2) as f64 / (1f64 - f64::powf(rho, 2f64)));
+    (rho, get_p_value(t_test, count))
+}
 
-    parse_ranks!(
-        feedbacks_with_data,
-        (
-            were_requirements_documents_written_score,
-            were_requirements_documents_written_rank
-        ),
-        (
-            was_initial_project_plan_written_score,
-            was_initial_project_plan_written_rank
-        ),
-        (
-            were_specification_documents_written_score,
-            were_specification_documents_written_rank
-        ),
-        (
-            were_technical_design_documents_written_score,
-            were_technical_design_documents_written_rank
-        ),
-        (
-            was_sbom_document_written_score,
-            was_sbom_document_written_rank
-        ),
-        (
-            were_draft_design_documents_written_score,
-            were_draft_design_documents_written_rank
-        ),
-        (
-            were_project_documents_reviewed_score,
-            were_project_documents_reviewed_rank
-        ),
-        (
-            were_mutability_and_side_effects_avoided_score,
-            were_mutability_and_side_effects_avoided_rank
-        ),
-        (
-            were_mutable_names_encoded_score,
-            were_mutable_names_encoded_rank
-        ),
-        (were_nulls_avoided_score, were_nulls_avoided_rank),
-        (was_non_english_used_score, was_non_english_used_rank),
-        (was_code_style_enforced_score, was_code_style_enforced_rank),
-        (was_code_refactored_score, was_code_refactored_rank),
-        (
-            were_posix_timestamps_used_score,
-            were_posix_timestamps_used_rank
-        ),
-        (
-            were_magic_numbers_replaced_score,
-            were_magic_numbers_replaced_rank
-        ),
-        (
-            were_critical_code_commented_score,
-            were_critical_code_commented_rank
-        ),
-        (
-            were_regex_patterns_commented_score,
-            were_regex_patterns_commented_rank
-        ),
-        (
-            were_todo_comments_avoided_score,
-            were_todo_comments_avoided_rank
-        ),
-        (
-            was_ai_generated_code_reviewed_score,
-            was_ai_generated_code_reviewed_rank
-        ),
-        (
-            was_branching_strategy_established_score,
-            was_branching_strategy_established_rank
-        ),
-        (
-            was_branch_naming_strategy_established_score,
-            was_branch_naming_strategy_established_rank
-        ),
-        (
-            was_commit_message_format_established_score,
-            was_commit_message_format_established_rank
-        ),
-        (
-            was_merging_strategy_established_score,
-            was_merging_strategy_established_rank
-        ),
-        (
-            were_higher_level_design_issues_considered_score,
-            were_higher_level_design_issues_considered_rank
-        ),
-        (was_reviewer_required_score, was_reviewer_required_rank),
-        (
-            were_formatter_and_linter_tools_established_score,
-            were_formatter_and_linter_tools_established_rank
-        ),
-        (
-            were_static_analysis_tools_established_score,
-            were_static_analysis_tools_established_rank
-        ),
-        (
-            were_semi_automated_tools_configured_score,
-            were_semi_automated_tools_configured_rank
-        ),
-        (
-            were_semi_automated_processes_run_score,
-            were_semi_automated_processes_run_rank
-        ),
-        (
-            were_automated_tests_run_score,
-            were_automated_tests_run_rank
-        ),
-        (
-            were_tests_written_like_production_code_score,
-            were_tests_written_like_production_code_rank
-        ),
-        (
-            were_unit_tests_written_with_boc_score,
-            were_unit_tests_written_with_boc_rank
-        )
-    );
+pub fn get_pearson_correlation(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len() as f64;
+    let mean_x = x.iter().sum::<f64>() / n;
+    let mean_y = y.iter().sum::<f64>() / n;
 
-    parse_correlations!(
-        feedbacks_with_data,
-        &mut results,
-        (
-            were_requirements_documents_written,
-            were_requirements_documents_written_score,
-            were_requirements_documents_written_rank
-        ),
-        (
-            was_initial_project_plan_written,
-            was_initial_project_plan_written_score,
-            was_initial_project_plan_written_rank
-        ),
-        (
-            were_specification_documents_written,
-            were_specification_documents_written_score,
-            were_specification_documents_written_rank
-        ),
-        (
-            were_technical_design_documents_written,
-            were_technical_design_documents_written_score,
-            were_technical_design_documents_written_rank
-        ),
-        (
-            was_sbom_document_written,
-            was_sbom_document_written_score,
-            was_sbom_document_written_rank
-        ),
-        (
-            were_draft_design_documents_written,
-            were_draft_design_documents_written_score,
-            were_draft_design_documents_written_rank
-        ),
-        (
-            were_project_documents_reviewed,
-            were_project_documents_reviewed_score,
-            were_project_documents_reviewed_rank
-        ),
-        (
-            were_mutability_and_side_effects_avoided,
-            were_mutability_and_side_effects_avoided_score,
-            were_mutability_and_side_effects_avoided_rank
-        ),
-        (
-            were_mutable_names_encoded,
-            were_mutable_names_encoded_score,
-            were_mutable_names_encoded_rank
-        ),
-        (
-            were_nulls_avoided,
-            were_nulls_avoided_score,
-            were_nulls_avoided_rank
-        ),
-        (
-            was_non_english_used,
-            was_non_english_used_score,
-            was_non_english_used_rank
-        ),
-        (
-            was_code_style_enforced,
-            was_code_style_enforced_score,
-            was_code_style_enforced_rank
-        ),
-        (
-            was_code_refactored,
-            was_code_refactored_score,
-            was_code_refactored_rank
-        ),
-        (
-            were_posix_timestamps_used,
-            were_posix_timestamps_used_score,
-            were_posix_timestamps_used_rank
-        ),
-        (
-            were_magic_numbers_replaced,
-            were_magic_numbers_replaced_score,
-            were_magic_numbers_replaced_rank
-        ),
-        (
-            were_critical_code_commented,
-            were_critical_code_commented_score,
-            were_critical_code_commented_rank
-        ),
-        (
-            were_regex_patterns_commented,
-            were_regex_patterns_commented_score,
-            were_regex_patterns_commented_rank
-        ),
-        (
-            were_todo_comments_avoided,
-            were_todo_comments_avoided_score,
-            were_todo_comments_avoided_rank
-        ),
-        (
-            was_ai_generated_code_reviewed,
-            was_ai_generated_code_reviewed_score,
-            was_ai_generated_code_reviewed_rank
-        ),
-        (
-            was_branching_strategy_established,
-            was_branching_strategy_established_score,
-            was_branching_strategy_established_rank
-        ),
-        (
-            was_branch_naming_strategy_established,
-            was_branch_naming_strategy_established_score,
-            was_branch_naming_strategy_established_rank
-        ),
-        (
-            was_commit_message_format_established,
-            was_commit_message_format_established_score,
-            was_commit_message_format_established_rank
-        ),
-        (
-            was_merging_strategy_established,
-            was_merging_strategy_established_score,
-            was_merging_strategy_established_rank
-        ),
-        (
-            were_higher_level_design_issues_considered,
-            were_higher_level_design_issues_considered_score,
-            were_higher_level_design_issues_considered_rank
-        ),
-        (
-            was_reviewer_required,
-            was_reviewer_required_score,
-            was_reviewer_required_rank
-        ),
-        (
-            were_formatter_and_linter_tools_established,
-            were_formatter_and_linter_tools_established_score,
-            were_formatter_and_linter_tools_established_rank
-        ),
-        (
-            were_static_analysis_tools_established,
-            were_static_analysis_tools_established_score,
-            were_static_analysis_tools_established_rank
-        ),
-        (
-            were_semi_automated_tools_configured,
-            were_semi_automated_tools_configured_score,
-            were_semi_automated_tools_configured_rank
-        ),
-        (
-            were_semi_automated_processes_run,
-            were_semi_automated_processes_run_score,
-            were_semi_automated_processes_run_rank
-        ),
-        (
-            were_automated_tests_run,
-            were_automated_tests_run_score,
-            were_automated_tests_run_rank
-        ),
-        (
-            were_tests_written_like_production_code,
-            were_tests_written_like_production_code_score,
-            were_tests_written_like_production_code_rank
-        ),
-        (
-            were_unit_tests_written_with_boc,
-            were_unit_tests_written_with_boc_score,
-            were_unit_tests_written_with_boc_rank
-        )
-    );
+    let mut product_sum = 0f64;
+    let mut x_sq_sum = 0f64;
+    let mut y_sq_sum = 0f64;
+
+    for (a, b) in x.iter().zip(y.iter()) {
+        product_sum += (a - mean_x) * (b - mean_y);
+        x_sq_sum += f64::powf(a - mean_x, 2f64);
+        y_sq_sum += f64::powf(b - mean_y, 2f64);
+    }
 
-    results
+    product_sum / (f64::sqrt(x_sq_sum) * f64::sqrt(y_sq_sum))
 }
 
+#[allow(clippy::needless_return)]
 pub fn get_p_value(t: f64, n: usize) -> f64 {
     let t_dist = StudentsT::new(0f64, 1f64, (n - 2) as f64)
         .expect("Failed to create StudentsT distribution");
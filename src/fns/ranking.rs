@@ -0,0 +1,43 @@
+use crate::util::types::{Ranking, TieMethod};
+
+/// Ranks the values with the highest value first. Tied values share a rank according to
+/// `method`, and the sizes of the tie groups are returned for tie corrections.
+pub fn get_ranking(values: &[f64], method: TieMethod) -> Ranking {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    // A stable sort keeps equal values in order of appearance for `TieMethod::Ordinal`.
+    order.sort_by(|a, b| values[*b].partial_cmp(&values[*a]).unwrap());
+
+    let mut ranks: Vec<f64> = vec![0f64; values.len()];
+    let mut tie_sizes: Vec<usize> = Vec::new();
+    let mut start = 0;
+
+    while start < order.len() {
+        let mut end = start;
+        while end + 1 < order.len() && values[order[end + 1]] == values[order[start]] {
+            end += 1;
+        }
+
+        for (offset, index) in order[start..=end].iter().enumerate() {
+            ranks[*index] = match method {
+                TieMethod::Average => (start + end + 2) as f64 / 2f64,
+                TieMethod::Min => (start + 1) as f64,
+                TieMethod::Max => (end + 1) as f64,
+                TieMethod::Dense => (tie_sizes.len() + 1) as f64,
+                TieMethod::Ordinal => (start + offset + 1) as f64,
+            };
+        }
+        tie_sizes.push(end - start + 1);
+        start = end + 1;
+    }
+
+    Ranking { ranks, tie_sizes }
+}
+
+/// Sum of `t³ - t` over the tie groups, the term shared by the tie corrections of rank
+/// statistics.
+pub fn get_tie_sum(tie_sizes: &[usize]) -> f64 {
+    tie_sizes
+        .iter()
+        .map(|t| f64::powf(*t as f64, 3f64) - *t as f64)
+        .sum()
+}
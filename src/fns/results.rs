@@ -0,0 +1,76 @@
+use crate::{
+    fns::{
+        cluster::{KMEANS_METHOD, KMEDOIDS_METHOD},
+        factor::{OBLIMIN_METHOD, PCA_METHOD, VARIMAX_METHOD},
+        ordinal::ORDINAL_METHOD,
+    },
+    util::{
+        args::Args,
+        types::{Feedback, InputMetadata, RunMetadata, RunParameters},
+        vars,
+    },
+};
+use sha2::{Digest, Sha256};
+use std::{
+    error::Error,
+    fs,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+static CORRELATION_METHOD: &str = "spearman";
+static P_VALUE_ADJUSTMENT: &str = "benjamini_hochberg";
+
+/// Records what produced a run: the hashed inputs, the tool version and every parameter
+/// that affects the published numbers.
+pub fn get_run_metadata(
+    args: &Args,
+    feedbacks: &[Feedback],
+) -> Result<RunMetadata, Box<dyn Error>> {
+    let mut inputs: Vec<InputMetadata> = Vec::new();
+    for input in args.inputs.iter() {
+        let digest = Sha256::digest(fs::read(&input.path)?);
+        inputs.push(InputMetadata {
+            wave: input.wave.to_owned(),
+            path: input.path.to_owned(),
+            sha256: digest.iter().map(|byte| format!("{:02x}", byte)).collect(),
+            row_count: feedbacks
+                .iter()
+                .filter(|f| f.origin.file == input.path && f.origin.wave == input.wave)
+                .count(),
+        });
+    }
+
+    Ok(RunMetadata {
+        tool: env!("CARGO_PKG_NAME").to_owned(),
+        version: env!("CARGO_PKG_VERSION").to_owned(),
+        run_timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        row_count: feedbacks.len(),
+        inputs,
+        parameters: RunParameters {
+            weighting_full: vars::WEIGHTING_FULL,
+            weighting_partial: vars::WEIGHTING_PARTIAL,
+            weighting_partial_negative: vars::WEIGHTING_PARTIAL_NEG,
+            weighting_full_negative: vars::WEIGHTING_FULL_NEG,
+            weighting_not_applicable: vars::WEIGHTING_NOT_APPLICABLE,
+            comparable_rho_multiplier: vars::COMPARABLE_RHO_MULTIPLIER,
+            round_decimal_places: vars::ROUND_DEMICAL_PLACES,
+            significance_level: vars::SIGNIFICANCE_LEVEL,
+            correlation_method: CORRELATION_METHOD.to_owned(),
+            p_value_adjustment: P_VALUE_ADJUSTMENT.to_owned(),
+            ordinal_regression_method: ORDINAL_METHOD.to_owned(),
+            factor_methods: [PCA_METHOD, VARIMAX_METHOD, OBLIMIN_METHOD]
+                .map(str::to_owned)
+                .to_vec(),
+            factor_eigenvalue_threshold: vars::FACTOR_EIGENVALUE_THRESHOLD,
+            cluster_methods: [KMEANS_METHOD, KMEDOIDS_METHOD].map(str::to_owned).to_vec(),
+            max_cluster_count: vars::MAX_CLUSTER_COUNT,
+            cluster_restarts: vars::CLUSTER_RESTARTS,
+            random_seed: vars::RANDOM_SEED,
+            group_columns: args.group_columns.clone(),
+            missing_data: args.missing_data.clone(),
+            optimal_scaling: args.optimal_scaling,
+            test_dont_know: args.test_dont_know,
+            tie_method: args.tie_method,
+        },
+    })
+}
@@ -0,0 +1,193 @@
+use crate::{
+    fns::{
+        question::get_question_info, rank::get_pearson_correlation, score::get_score_columns,
+        stats::get_isotonic_fit, util::round_value,
+    },
+    util::{
+        types::{CategoryScalingData, Feedback, QuestionOptimalScalingData, ScoreColumns},
+        vars,
+    },
+};
+
+/// Estimates category values for every practice question that maximise Pearson's r with the
+/// quality estimate while keeping the order of the answers: the mean quality of each category,
+/// fitted by monotone regression in whichever direction associates more strongly. The values
+/// are cross-validated over `CROSS_VALIDATION_FOLDS` folds of respondents to show whether they
+/// generalise beyond the answers they were estimated from.
+pub fn calculate_optimal_scalings(feedbacks: &[Feedback]) -> Vec<QuestionOptimalScalingData> {
+    let columns: ScoreColumns = get_score_columns(feedbacks);
+
+    columns
+        .questions
+        .iter()
+        .filter_map(|column| {
+            let (scores, qualities) = columns.get_applicable_scores(column);
+            if scores.len() < 3 {
+                return None;
+            }
+
+            // Undefined without variation, as for the correlations
+            let fixed_r = get_pearson_correlation(&scores, &qualities);
+            if fixed_r.is_nan() {
+                return None;
+            }
+
+            let mut categories: Vec<f64> = scores.clone();
+            categories.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            categories.dedup();
+
+            let values: Option<Vec<f64>> = get_scaling(&categories, &scores, &qualities);
+            let optimal_r: Option<f64> = values.as_ref().map(|values| {
+                round_value(get_pearson_correlation(
+                    &get_scaled_scores(&categories, values, &scores),
+                    &qualities,
+                ))
+            });
+            let cross_validated_r: Option<f64> =
+                get_cross_validated_r(&categories, &scores, &qualities);
+
+            Some(QuestionOptimalScalingData {
+                question: column.question.to_owned(),
+                question_info: get_question_info(column.question),
+                answer_count: scores.len(),
+                categories: categories
+                    .iter()
+                    .enumerate()
+                    .map(|(index, category)| {
+                        let category_qualities: Vec<f64> =
+                            get_category_qualities(*category, &scores, &qualities);
+
+                        CategoryScalingData {
+                            weighting: *category as i8,
+                            answer_count: category_qualities.len(),
+                            quality_mean: round_value(
+                                category_qualities.iter().sum::<f64>()
+                                    / category_qualities.len() as f64,
+                            ),
+                            value: values.as_ref().map(|values| round_value(values[index])),
+                        }
+                    })
+                    .collect(),
+                fixed_r: round_value(fixed_r),
+                optimal_r,
+                cross_validated_r,
+                is_improvement: cross_validated_r.is_some_and(|r| r.abs() > fixed_r.abs()),
+            })
+        })
+        .collect()
+}
+
+/// Values of `categories` estimated from the given answers, rescaled from the lowest to the
+/// highest fixed weighting. Categories without answers take the value of the closest lower
+/// category, or of the lowest one with answers. `None` when the fit pools every category into
+/// one value.
+fn get_scaling(categories: &[f64], scores: &[f64], qualities: &[f64]) -> Option<Vec<f64>> {
+    let present: Vec<(usize, f64, f64)> = categories
+        .iter()
+        .enumerate()
+        .filter_map(|(index, category)| {
+            let category_qualities = get_category_qualities(*category, scores, qualities);
+            if category_qualities.is_empty() {
+                return None;
+            }
+
+            let count = category_qualities.len() as f64;
+            Some((index, category_qualities.iter().sum::<f64>() / count, count))
+        })
+        .collect();
+
+    let means: Vec<f64> = present.iter().map(|(_, mean, _)| *mean).collect();
+    let weights: Vec<f64> = present.iter().map(|(_, _, count)| *count).collect();
+    let negated_means: Vec<f64> = means.iter().map(|mean| -mean).collect();
+
+    // Both fits increase with the answer order; the second is associated negatively.
+    let fits: [Vec<f64>; 2] = [
+        get_isotonic_fit(&means, &weights),
+        get_isotonic_fit(&negated_means, &weights),
+    ];
+    let present_categories: Vec<f64> = present.iter().map(|(i, _, _)| categories[*i]).collect();
+    let get_strength = |fit: &Vec<f64>| {
+        let r = get_pearson_correlation(
+            &get_scaled_scores(&present_categories, fit, scores),
+            qualities,
+        );
+        if r.is_nan() { 0f64 } else { r.abs() }
+    };
+    let fit: &Vec<f64> = fits
+        .iter()
+        .max_by(|a, b| get_strength(a).partial_cmp(&get_strength(b)).unwrap())
+        .unwrap();
+
+    let mut values: Vec<f64> = Vec::new();
+    let mut present_values = present.iter().zip(fit.iter()).peekable();
+    for index in 0..categories.len() {
+        match present_values.peek() {
+            Some(((present_index, _, _), value)) if *present_index == index => {
+                values.push(**value);
+                present_values.next();
+            }
+            _ => values.push(values.last().copied().unwrap_or(fit[0])),
+        }
+    }
+
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if max - min <= f64::EPSILON {
+        return None;
+    }
+
+    let (low, high) = (vars::WEIGHTING_FULL_NEG as f64, vars::WEIGHTING_FULL as f64);
+    Some(
+        values
+            .iter()
+            .map(|value| low + (value - min) / (max - min) * (high - low))
+            .collect(),
+    )
+}
+
+/// Pearson's r of values estimated without each fold of respondents, applied to that fold.
+/// A fold whose training answers pool into one value gets the middle of the scale.
+fn get_cross_validated_r(categories: &[f64], scores: &[f64], qualities: &[f64]) -> Option<f64> {
+    let folds = vars::CROSS_VALIDATION_FOLDS;
+    if scores.len() < folds {
+        return None;
+    }
+
+    let middle = (vars::WEIGHTING_FULL_NEG + vars::WEIGHTING_FULL) as f64 / 2f64;
+    let mut predictions: Vec<f64> = vec![middle; scores.len()];
+
+    for fold in 0..folds {
+        let (training_scores, training_qualities): (Vec<f64>, Vec<f64>) = (0..scores.len())
+            .filter(|i| i % folds != fold)
+            .map(|i| (scores[i], qualities[i]))
+            .unzip();
+
+        if let Some(values) = get_scaling(categories, &training_scores, &training_qualities) {
+            for i in (fold..scores.len()).step_by(folds) {
+                predictions[i] = get_scaled_scores(categories, &values, &scores[i..=i])[0];
+            }
+        }
+    }
+
+    let r = get_pearson_correlation(&predictions, qualities);
+    (!r.is_nan()).then_some(round_value(r))
+}
+
+fn get_scaled_scores(categories: &[f64], values: &[f64], scores: &[f64]) -> Vec<f64> {
+    scores
+        .iter()
+        .map(|score| {
+            let index = categories.iter().position(|c| c == score).unwrap();
+            values[index]
+        })
+        .collect()
+}
+
+fn get_category_qualities(category: f64, scores: &[f64], qualities: &[f64]) -> Vec<f64> {
+    scores
+        .iter()
+        .zip(qualities.iter())
+        .filter(|(score, _)| **score == category)
+        .map(|(_, quality)| *quality)
+        .collect()
+}
@@ -0,0 +1,73 @@
+use crate::{
+    fns::{question::get_questions_of_kind, ranking::get_ranking},
+    util::{
+        types::{
+            Feedback, QuestionKind, QuestionScores, Ranking, ScoreColumns, TieMethod,
+            WeightingScheme,
+        },
+        vars,
+    },
+};
+
+/// Scores every practice answer into one column per question and ranks the applicable
+/// scores of each column with average ranks, as Spearman's rho needs.
+pub fn get_score_columns(feedbacks: &[Feedback]) -> ScoreColumns {
+    get_rescored_columns(feedbacks, |score| score)
+}
+
+/// Score columns with the answer weightings replaced by those of `scheme`.
+pub fn get_weighted_score_columns(
+    feedbacks: &[Feedback],
+    scheme: &WeightingScheme,
+) -> ScoreColumns {
+    get_rescored_columns(feedbacks, |score| scheme.get_score(score))
+}
+
+fn get_rescored_columns(feedbacks: &[Feedback], rescore: impl Fn(i8) -> i8) -> ScoreColumns {
+    let questions: Vec<QuestionScores> = get_questions_of_kind(QuestionKind::Practice)
+        .map(|question| {
+            let scores: Vec<i8> = feedbacks
+                .iter()
+                .map(|f| {
+                    question
+                        .get_score(f)
+                        .map_or(vars::WEIGHTING_NOT_APPLICABLE, |score| rescore(score as i8))
+                })
+                .collect();
+
+            let applicable_scores: Vec<f64> = scores
+                .iter()
+                .filter(|score| **score != vars::WEIGHTING_NOT_APPLICABLE)
+                .map(|score| *score as f64)
+                .collect();
+            let Ranking { ranks, tie_sizes } = get_ranking(&applicable_scores, TieMethod::Average);
+            let mut applicable_ranks = ranks.into_iter();
+            let ranks: Vec<Option<f64>> = scores
+                .iter()
+                .map(|score| {
+                    if *score == vars::WEIGHTING_NOT_APPLICABLE {
+                        None
+                    } else {
+                        applicable_ranks.next()
+                    }
+                })
+                .collect();
+
+            QuestionScores {
+                question: question.field_name,
+                scores,
+                ranks,
+                tie_sizes,
+            }
+        })
+        .collect();
+
+    ScoreColumns {
+        ids: feedbacks.iter().map(|f| f.id).collect(),
+        qualities: feedbacks
+            .iter()
+            .map(|f| f.project_quality_estimate)
+            .collect(),
+        questions,
+    }
+}
@@ -0,0 +1,231 @@
+use crate::{
+    fns::question::{QUESTION_CATEGORIES, get_practice, get_questions_of_kind},
+    util::{
+        types::{Question, QuestionKind, SimulatedSurvey, SimulationConfig},
+        vars,
+    },
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use statrs::distribution::{ContinuousCDF, Normal};
+use std::f64::consts::PI;
+
+/// 2025-03-01 09:00:00 in seconds since the Unix epoch.
+static FIRST_SUBMISSION: i64 = 1_740_819_600;
+static SUBMISSION_INTERVAL: i64 = 600;
+static SECONDS_PER_DAY: i64 = 86_400;
+
+/// Generates survey responses from a Gaussian copula. Each respondent has a latent quality,
+/// and each practice a latent value correlated with it as configured; the latent values are
+/// cut into the answers of the question at the quantiles of the configured frequencies. The
+/// same seed always gives the same responses.
+pub fn simulate_survey(config: &SimulationConfig) -> Result<SimulatedSurvey, String> {
+    validate_simulation_config(config)?;
+
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let answer_thresholds: Vec<f64> = get_thresholds(&config.answer_weights);
+    let quality_thresholds: Vec<f64> = get_thresholds(&config.quality_weights);
+    let columns: Vec<&Question> = get_survey_columns();
+    let loadings: Vec<f64> = columns.iter().map(|q| get_loading(config, q)).collect();
+
+    let mut headers: Vec<String> = vec!["Timestamp".to_owned()];
+    headers.extend(columns.iter().map(|q| get_header(q)));
+
+    let rows: Vec<Vec<String>> = (0..config.respondent_count)
+        .map(|respondent| {
+            let quality: f64 = get_standard_normal(&mut rng);
+            let mut row: Vec<String> = vec![get_timestamp(respondent)];
+
+            for (question, loading) in columns.iter().zip(loadings.iter()) {
+                let cell: String = match question.kind {
+                    QuestionKind::Practice => {
+                        let latent: f64 = loading * quality
+                            + f64::sqrt(1f64 - f64::powf(*loading, 2f64))
+                                * get_standard_normal(&mut rng);
+
+                        if rng.random::<f64>() < config.not_applicable_rate {
+                            question.unscored_answers
+                                [rng.random_range(0..question.unscored_answers.len())]
+                            .to_owned()
+                        } else {
+                            question.answers[get_category(latent, &answer_thresholds)].to_owned()
+                        }
+                    }
+                    QuestionKind::Impact => {
+                        if rng.random::<f64>() < config.not_applicable_rate {
+                            String::new()
+                        } else {
+                            rng.random_range(vars::IMPACT_RATING_MIN..=vars::IMPACT_RATING_MAX)
+                                .to_string()
+                        }
+                    }
+                    QuestionKind::Outcome => {
+                        (get_category(quality, &quality_thresholds) + 1).to_string()
+                    }
+                };
+                row.push(cell);
+            }
+
+            row
+        })
+        .collect();
+
+    Ok(SimulatedSurvey { headers, rows })
+}
+
+pub fn validate_simulation_config(config: &SimulationConfig) -> Result<(), String> {
+    if config.respondent_count == 0 {
+        return Err("At least one respondent is required".to_owned());
+    }
+
+    if !(0f64..=1f64).contains(&config.not_applicable_rate) {
+        return Err(format!(
+            "Not applicable rate is not between 0 and 1: {}",
+            config.not_applicable_rate
+        ));
+    }
+
+    for weights in [&config.answer_weights[..], &config.quality_weights[..]] {
+        if weights.iter().any(|w| !w.is_finite() || *w < 0f64)
+            || weights.iter().sum::<f64>() <= 0f64
+        {
+            return Err(format!(
+                "Weights must be non-negative and not all zero: {:?}",
+                weights
+            ));
+        }
+    }
+
+    for (i, (key, rho)) in config.correlations.iter().enumerate() {
+        let question: &Question =
+            get_practice(key).ok_or_else(|| format!("Unknown practice question: {}", key))?;
+
+        if !(-1f64..=1f64).contains(rho) {
+            return Err(format!(
+                "Correlation of {} is not between -1 and 1: {}",
+                key, rho
+            ));
+        }
+
+        if config.correlations[..i]
+            .iter()
+            .any(|(other, _)| get_practice(other).is_some_and(|q| q.id == question.id))
+        {
+            return Err(format!("Correlation of {} is given twice", question.id));
+        }
+    }
+
+    Ok(())
+}
+
+/// Columns in the order of the survey form: the practices of each category followed by their
+/// impact ratings, then the quality estimate.
+fn get_survey_columns() -> Vec<&'static Question> {
+    let mut columns: Vec<&Question> = Vec::new();
+    for category in QUESTION_CATEGORIES.iter() {
+        for kind in [QuestionKind::Practice, QuestionKind::Impact] {
+            columns.extend(get_questions_of_kind(kind).filter(|q| q.category == *category));
+        }
+    }
+    columns.extend(get_questions_of_kind(QuestionKind::Outcome));
+    columns
+}
+
+/// Practices whose header in the form export ends with a space after the question text.
+const SPACED_HEADER_FIELD_NAMES: [&str; 2] = [
+    "were_technical_design_documents_written",
+    "was_sbom_document_written",
+];
+
+/// The header of the question's column exactly as the form export writes it.
+fn get_header(question: &Question) -> String {
+    match question.kind {
+        QuestionKind::Impact => format!("{}[{}]", vars::IMPACT_HEADER_PREFIX, question.text),
+        _ if SPACED_HEADER_FIELD_NAMES.contains(&question.field_name) => {
+            format!("{} ", question.text)
+        }
+        _ => question.text.to_owned(),
+    }
+}
+
+/// Pearson correlation of the latent practice value with the latent quality that gives the
+/// target Spearman's rho for bivariate normal variables, or 0 for uncorrelated practices.
+/// Cutting the latent values into a few answers weakens the observed correlation.
+fn get_loading(config: &SimulationConfig, question: &Question) -> f64 {
+    if question.kind != QuestionKind::Practice {
+        return 0f64;
+    }
+
+    config
+        .correlations
+        .iter()
+        .find(|(key, _)| get_practice(key).is_some_and(|q| q.id == question.id))
+        .map_or(0f64, |(_, rho)| 2f64 * f64::sin(PI * rho / 6f64))
+}
+
+/// Standard normal quantiles between the categories of the given relative frequencies.
+fn get_thresholds(weights: &[f64]) -> Vec<f64> {
+    let normal = Normal::standard();
+    let total: f64 = weights.iter().sum();
+    let mut cumulative = 0f64;
+
+    weights[..weights.len() - 1]
+        .iter()
+        .map(|weight| {
+            cumulative += weight / total;
+            match cumulative {
+                c if c <= 0f64 => f64::NEG_INFINITY,
+                c if c >= 1f64 => f64::INFINITY,
+                c => normal.inverse_cdf(c),
+            }
+        })
+        .collect()
+}
+
+fn get_category(value: f64, thresholds: &[f64]) -> usize {
+    thresholds.iter().filter(|t| value > **t).count()
+}
+
+/// Box-Muller transform of two uniform draws.
+fn get_standard_normal(rng: &mut StdRng) -> f64 {
+    let u1: f64 = 1f64 - rng.random::<f64>();
+    let u2: f64 = rng.random::<f64>();
+    f64::sqrt(-2f64 * f64::ln(u1)) * f64::cos(2f64 * PI * u2)
+}
+
+/// Submission time of a respondent in the form export's `YYYY/MM/DD HH:MM:SS` format.
+fn get_timestamp(respondent: usize) -> String {
+    let seconds: i64 = FIRST_SUBMISSION + respondent as i64 * SUBMISSION_INTERVAL;
+    let (year, month, day) = get_civil_date(seconds.div_euclid(SECONDS_PER_DAY));
+    let time: i64 = seconds.rem_euclid(SECONDS_PER_DAY);
+
+    format!(
+        "{:04}/{:02}/{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// Gregorian date of a day count since 1970-01-01, following Howard Hinnant's
+/// `civil_from_days`.
+fn get_civil_date(days: i64) -> (i64, i64, i64) {
+    let z: i64 = days + 719_468;
+    let era: i64 = z.div_euclid(146_097);
+    let day_of_era: i64 = z - era * 146_097;
+    let year_of_era: i64 =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year: i64 = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index: i64 = (5 * day_of_year + 2) / 153;
+    let day: i64 = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month: i64 = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year: i64 = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
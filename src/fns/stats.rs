@@ -0,0 +1,302 @@
+use crate::{
+    fns::ranking::{get_ranking, get_tie_sum},
+    util::{
+        types::{Ranking, TieMethod},
+        vars,
+    },
+};
+use statrs::distribution::{ChiSquared, ContinuousCDF, Normal, StudentsT};
+
+// See: Fieller, E. C., Hartley, H. O., & Pearson, E. S. (1957). Tests for rank correlation
+// coefficients. I. Biometrika, 44(3/4), 470-481.
+pub static SPEARMAN_FISHER_VARIANCE_FACTOR: f64 = 1.06;
+pub static PEARSON_FISHER_VARIANCE_FACTOR: f64 = 1f64;
+static MAX_FISHER_RHO: f64 = 0.99999;
+
+/// Tests whether independent Spearman correlations are equal using Fisher's z with
+/// variance `1.06 / (n - 3)`. For two groups this is the usual two-sided z-test squared.
+/// Takes `(rho, n)` pairs and returns `(chi², df, p)`, or `None` with fewer than two usable groups.
+pub fn get_correlation_homogeneity(correlations: &[(f64, usize)]) -> Option<(f64, usize, f64)> {
+    let groups: Vec<(f64, f64)> = correlations
+        .iter()
+        .filter(|(rho, n)| *n > 3 && rho.is_finite())
+        .map(|(rho, n)| {
+            let z = rho.clamp(-MAX_FISHER_RHO, MAX_FISHER_RHO).atanh();
+            let weight = (*n - 3) as f64 / SPEARMAN_FISHER_VARIANCE_FACTOR;
+            (z, weight)
+        })
+        .collect();
+
+    if groups.len() < 2 {
+        return None;
+    }
+
+    let weight_sum: f64 = groups.iter().map(|(_, w)| w).sum();
+    let mean_z = groups.iter().map(|(z, w)| z * w).sum::<f64>() / weight_sum;
+    let statistic: f64 = groups
+        .iter()
+        .map(|(z, w)| w * f64::powf(z - mean_z, 2f64))
+        .sum();
+
+    let df = groups.len() - 1;
+    Some((statistic, df, get_chi_squared_p_value(statistic, df)))
+}
+
+/// Benjamini-Hochberg false discovery rate adjustment; the result keeps the input order.
+pub fn get_adjusted_p_values(p_values: &[f64]) -> Vec<f64> {
+    let n = p_values.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|a, b| p_values[*b].partial_cmp(&p_values[*a]).unwrap());
+
+    let mut adjusted = vec![0f64; n];
+    let mut running_min = 1f64;
+    for (i, index) in order.into_iter().enumerate() {
+        let rank = n - i;
+        running_min = running_min.min(p_values[index] * n as f64 / rank as f64);
+        adjusted[index] = running_min;
+    }
+    adjusted
+}
+
+/// Confidence interval for Spearman's rho at `1 - SIGNIFICANCE_LEVEL` from Fisher's z with
+/// variance `1.06 / (n - 3)`. With three or fewer answers the interval is the whole range.
+pub fn get_correlation_confidence_interval(rho: f64, n: usize) -> (f64, f64) {
+    if n <= 3 {
+        return (-1f64, 1f64);
+    }
+
+    let normal = Normal::new(0f64, 1f64).expect("Failed to create Normal distribution");
+    let z_critical = normal.inverse_cdf(1f64 - vars::SIGNIFICANCE_LEVEL / 2f64);
+    let z = rho.clamp(-MAX_FISHER_RHO, MAX_FISHER_RHO).atanh();
+    let standard_error = f64::sqrt(SPEARMAN_FISHER_VARIANCE_FACTOR / (n - 3) as f64);
+
+    (
+        (z - z_critical * standard_error).tanh(),
+        (z + z_critical * standard_error).tanh(),
+    )
+}
+
+/// Power of the one-sided test of a correlation at `SIGNIFICANCE_LEVEL`, which the reported
+/// p-values come from, using Fisher's z with variance `variance_factor / (n - 3)`.
+pub fn get_correlation_power(rho: f64, n: usize, variance_factor: f64) -> f64 {
+    if n <= 3 || !rho.is_finite() {
+        return 0f64;
+    }
+
+    let normal = Normal::new(0f64, 1f64).expect("Failed to create Normal distribution");
+    let z_critical = normal.inverse_cdf(1f64 - vars::SIGNIFICANCE_LEVEL);
+    let z = rho.abs().min(MAX_FISHER_RHO).atanh();
+
+    normal.cdf(z * f64::sqrt((n - 3) as f64 / variance_factor) - z_critical)
+}
+
+/// Smallest number of answers for which `get_correlation_power` reaches `power`, or `None`
+/// without a correlation to detect.
+pub fn get_required_sample_size(rho: f64, power: f64, variance_factor: f64) -> Option<usize> {
+    if rho == 0f64 || !rho.is_finite() {
+        return None;
+    }
+
+    let normal = Normal::new(0f64, 1f64).expect("Failed to create Normal distribution");
+    let z_sum = normal.inverse_cdf(1f64 - vars::SIGNIFICANCE_LEVEL) + normal.inverse_cdf(power);
+    let z = rho.abs().min(MAX_FISHER_RHO).atanh();
+
+    Some((variance_factor * f64::powf(z_sum / z, 2f64) + 3f64).ceil() as usize)
+}
+
+/// Two-sided test for the difference between two independent Spearman correlations using
+/// Fisher's z with variance `1.06 / (n - 3)`. Takes `(rho, n)` pairs and returns `(z, p)`.
+pub fn get_correlation_difference(a: (f64, usize), b: (f64, usize)) -> Option<(f64, f64)> {
+    if a.1 <= 3 || b.1 <= 3 || !a.0.is_finite() || !b.0.is_finite() {
+        return None;
+    }
+
+    let z_a = a.0.clamp(-MAX_FISHER_RHO, MAX_FISHER_RHO).atanh();
+    let z_b = b.0.clamp(-MAX_FISHER_RHO, MAX_FISHER_RHO).atanh();
+    let standard_error = f64::sqrt(
+        SPEARMAN_FISHER_VARIANCE_FACTOR / (a.1 - 3) as f64
+            + SPEARMAN_FISHER_VARIANCE_FACTOR / (b.1 - 3) as f64,
+    );
+
+    let z = (z_b - z_a) / standard_error;
+    Some((z, get_normal_p_value(z)))
+}
+
+/// Pools Spearman correlations of `n` answers estimated on imputed datasets by Rubin's rules
+/// on Fisher's z with within-imputation variance `1.06 / (n - 3)`, with the degrees of freedom
+/// of Barnard and Rubin (1999). Returns `(rho, ci_lower, ci_upper, p)` with the interval at
+/// `1 - SIGNIFICANCE_LEVEL` and the one-sided p-value of the correlation tests, or `None` with
+/// three or fewer answers or without a finite correlation.
+pub fn get_pooled_correlation(rhos: &[f64], n: usize) -> Option<(f64, f64, f64, f64)> {
+    if n <= 3 || rhos.is_empty() || rhos.iter().any(|rho| !rho.is_finite()) {
+        return None;
+    }
+
+    let m = rhos.len() as f64;
+    let zs: Vec<f64> = rhos
+        .iter()
+        .map(|rho| rho.clamp(-MAX_FISHER_RHO, MAX_FISHER_RHO).atanh())
+        .collect();
+    let mean_z = zs.iter().sum::<f64>() / m;
+    let within_variance = SPEARMAN_FISHER_VARIANCE_FACTOR / (n - 3) as f64;
+    let between_variance = if rhos.len() > 1 {
+        zs.iter().map(|z| f64::powf(z - mean_z, 2f64)).sum::<f64>() / (m - 1f64)
+    } else {
+        0f64
+    };
+    let total_variance = within_variance + (1f64 + 1f64 / m) * between_variance;
+
+    // The share of the variance due to the imputations shrinks the degrees of freedom from
+    // those of the complete data
+    let complete_df = (n - 3) as f64;
+    let missing_share = (1f64 + 1f64 / m) * between_variance / total_variance;
+    let observed_df =
+        (complete_df + 1f64) / (complete_df + 3f64) * complete_df * (1f64 - missing_share);
+    let df = if between_variance > 0f64 {
+        let imputation_df = (m - 1f64) / f64::powf(missing_share, 2f64);
+        1f64 / (1f64 / imputation_df + 1f64 / observed_df)
+    } else {
+        observed_df
+    };
+
+    let t_dist = StudentsT::new(0f64, 1f64, df).expect("Failed to create StudentsT distribution");
+    let t_critical = t_dist.inverse_cdf(1f64 - vars::SIGNIFICANCE_LEVEL / 2f64);
+    let standard_error = total_variance.sqrt();
+
+    Some((
+        mean_z.tanh(),
+        (mean_z - t_critical * standard_error).tanh(),
+        (mean_z + t_critical * standard_error).tanh(),
+        1f64 - t_dist.cdf((mean_z / standard_error).abs()),
+    ))
+}
+
+/// Mann-Whitney U test with tie-corrected normal approximation.
+/// Returns `(U, z, p)` where `U` counts pairs in which `b` exceeds `a` and a positive `z`
+/// means `b` tends to be larger.
+pub fn get_mann_whitney(a: &[f64], b: &[f64]) -> Option<(f64, f64, f64)> {
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+
+    let values: Vec<f64> = a.iter().chain(b.iter()).copied().collect();
+    let total = values.len() as f64;
+    let (count_a, count_b) = (a.len() as f64, b.len() as f64);
+
+    // Fractional ranks run from the highest value, so flip them to ascending.
+    let ranking: Ranking = get_ranking(&values, TieMethod::Average);
+    let ranks: Vec<f64> = ranking
+        .ranks
+        .iter()
+        .map(|rank| total + 1f64 - rank)
+        .collect();
+    let rank_sum_b: f64 = ranks[a.len()..].iter().sum();
+    let u = rank_sum_b - count_b * (count_b + 1f64) / 2f64;
+
+    let variance = count_a * count_b / 12f64
+        * ((total + 1f64) - get_tie_sum(&ranking.tie_sizes) / (total * (total - 1f64)));
+
+    if variance <= 0f64 {
+        return None;
+    }
+
+    let z = (u - count_a * count_b / 2f64) / variance.sqrt();
+    Some((u, z, get_normal_p_value(z)))
+}
+
+/// Two-sided pooled z-test for the difference between two proportions, given as
+/// `(successes, trials)`. Returns `(z, p)` with a positive `z` when `b` is larger.
+pub fn get_proportion_difference(a: (usize, usize), b: (usize, usize)) -> Option<(f64, f64)> {
+    if a.1 == 0 || b.1 == 0 {
+        return None;
+    }
+
+    let (proportion_a, proportion_b) = (a.0 as f64 / a.1 as f64, b.0 as f64 / b.1 as f64);
+    let pooled = (a.0 + b.0) as f64 / (a.1 + b.1) as f64;
+    let standard_error =
+        f64::sqrt(pooled * (1f64 - pooled) * (1f64 / a.1 as f64 + 1f64 / b.1 as f64));
+
+    if standard_error == 0f64 {
+        return None;
+    }
+
+    let z = (proportion_b - proportion_a) / standard_error;
+    Some((z, get_normal_p_value(z)))
+}
+
+/// Kruskal-Wallis H test with tie correction. Returns `(H, df, p)`, or `None` when fewer
+/// than two groups have observations or every observation is tied.
+pub fn get_kruskal_wallis(groups: &[Vec<f64>]) -> Option<(f64, usize, f64)> {
+    let groups: Vec<&Vec<f64>> = groups.iter().filter(|g| !g.is_empty()).collect();
+
+    if groups.len() < 2 {
+        return None;
+    }
+
+    let values: Vec<f64> = groups.iter().flat_map(|g| g.iter().copied()).collect();
+    let Ranking { ranks, tie_sizes } = get_ranking(&values, TieMethod::Average);
+    let total = values.len() as f64;
+
+    let mut offset = 0;
+    let mut rank_term = 0f64;
+
+    for group in groups.iter() {
+        let rank_sum: f64 = ranks[offset..offset + group.len()].iter().sum();
+        rank_term += f64::powf(rank_sum, 2f64) / group.len() as f64;
+        offset += group.len();
+    }
+
+    let statistic = 12f64 / (total * (total + 1f64)) * rank_term - 3f64 * (total + 1f64);
+    let tie_correction = 1f64 - get_tie_sum(&tie_sizes) / (f64::powf(total, 3f64) - total);
+
+    if tie_correction <= 0f64 {
+        return None;
+    }
+
+    let corrected_statistic = statistic / tie_correction;
+    let df = groups.len() - 1;
+    Some((
+        corrected_statistic,
+        df,
+        get_chi_squared_p_value(corrected_statistic, df),
+    ))
+}
+
+pub fn get_chi_squared_p_value(statistic: f64, df: usize) -> f64 {
+    1f64 - ChiSquared::new(df as f64)
+        .expect("Failed to create ChiSquared distribution")
+        .cdf(statistic)
+}
+
+/// Two-sided p-value of a standard normal statistic.
+pub fn get_normal_p_value(z: f64) -> f64 {
+    let normal = Normal::new(0f64, 1f64).expect("Failed to create Normal distribution");
+    2f64 * (1f64 - normal.cdf(z.abs()))
+}
+
+/// Weighted least-squares fit that never decreases in the order of `values`, by pooling
+/// adjacent violators into their weighted mean.
+pub fn get_isotonic_fit(values: &[f64], weights: &[f64]) -> Vec<f64> {
+    // Each block is (mean, weight, length)
+    let mut blocks: Vec<(f64, f64, usize)> = Vec::new();
+
+    for (value, weight) in values.iter().zip(weights.iter()) {
+        blocks.push((*value, *weight, 1));
+
+        while blocks.len() > 1 && blocks[blocks.len() - 2].0 > blocks[blocks.len() - 1].0 {
+            let (mean_b, weight_b, length_b) = blocks.pop().unwrap();
+            let (mean_a, weight_a, length_a) = blocks.pop().unwrap();
+            let weight = weight_a + weight_b;
+            blocks.push((
+                (mean_a * weight_a + mean_b * weight_b) / weight,
+                weight,
+                length_a + length_b,
+            ));
+        }
+    }
+
+    blocks
+        .into_iter()
+        .flat_map(|(mean, _, length)| std::iter::repeat_n(mean, length))
+        .collect()
+}
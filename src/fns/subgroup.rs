@@ -0,0 +1,157 @@
+use crate::{
+    fns::{
+        diff::calculate_diffs,
+        impact::{get_impact_average, get_impact_averages, get_impact_ratings},
+        question::get_question_info,
+        rank::calculate_rankings,
+        stats::{get_correlation_homogeneity, get_kruskal_wallis},
+        util::round_value,
+    },
+    util::types::{
+        Feedback, SubgroupAnalysisData, SubgroupCorrelationTestData, SubgroupData,
+        SubgroupImpactTestData,
+    },
+};
+
+type ImpactRatings = Vec<(&'static str, Option<f32>)>;
+
+/// Runs the correlation, impact and diff analyses separately for every value of each
+/// grouping column and tests the groups against each other. Respondents who left the
+/// grouping question blank are left out of that column's analysis.
+pub fn calculate_subgroup_analyses(
+    feedbacks: &[Feedback],
+    group_columns: &[String],
+) -> Vec<SubgroupAnalysisData> {
+    group_columns
+        .iter()
+        .map(|column| {
+            let mut values: Vec<&String> = feedbacks
+                .iter()
+                .filter_map(|f| f.groups.get(column))
+                .filter(|value| !value.is_empty())
+                .collect();
+            values.sort();
+            values.dedup();
+
+            let subsets: Vec<(&String, Vec<Feedback>)> = values
+                .into_iter()
+                .map(|value| {
+                    let subset: Vec<Feedback> = feedbacks
+                        .iter()
+                        .filter(|f| f.groups.get(column) == Some(value))
+                        .cloned()
+                        .collect();
+                    (value, subset)
+                })
+                .collect();
+
+            let groups: Vec<SubgroupData> = subsets
+                .iter()
+                .map(|(value, subset)| {
+                    let correlations = calculate_rankings(subset);
+                    let impacts = get_impact_averages(subset);
+                    let impact_average = get_impact_average(&impacts);
+                    let diffs = calculate_diffs(&correlations, &impacts);
+
+                    SubgroupData {
+                        group: (*value).to_owned(),
+                        respondent_count: subset.len(),
+                        correlations,
+                        impacts,
+                        impact_average,
+                        diffs,
+                    }
+                })
+                .collect();
+
+            let subset_feedbacks: Vec<&Vec<Feedback>> =
+                subsets.iter().map(|(_, subset)| subset).collect();
+
+            SubgroupAnalysisData {
+                column: column.to_owned(),
+                correlation_tests: get_correlation_tests(&groups),
+                impact_tests: get_impact_tests(&subset_feedbacks),
+                groups,
+            }
+        })
+        .collect()
+}
+
+fn get_correlation_tests(groups: &[SubgroupData]) -> Vec<SubgroupCorrelationTestData> {
+    let mut questions: Vec<&String> = groups
+        .iter()
+        .flat_map(|g| g.correlations.iter().map(|c| &c.question))
+        .collect();
+    questions.sort();
+    questions.dedup();
+
+    let mut tests: Vec<SubgroupCorrelationTestData> = questions
+        .into_iter()
+        .filter_map(|question| {
+            let correlations: Vec<(f64, usize)> = groups
+                .iter()
+                .filter_map(|g| g.correlations.iter().find(|c| c.question == *question))
+                .map(|c| (c.rho, c.answer_count))
+                .collect();
+
+            let (chi_squared, df, p_value) = get_correlation_homogeneity(&correlations)?;
+
+            Some(SubgroupCorrelationTestData {
+                question: question.to_owned(),
+                question_info: get_question_info(question),
+                group_count: df + 1,
+                chi_squared: round_value(chi_squared),
+                df,
+                p_value: round_value(p_value),
+            })
+        })
+        .collect();
+
+    tests.sort_by(|a, b| a.p_value.partial_cmp(&b.p_value).unwrap());
+    tests
+}
+
+fn get_impact_tests(subsets: &[&Vec<Feedback>]) -> Vec<SubgroupImpactTestData> {
+    let ratings: Vec<Vec<ImpactRatings>> = subsets
+        .iter()
+        .map(|subset| subset.iter().map(get_impact_ratings).collect())
+        .collect();
+
+    let questions: Vec<&'static str> = ratings
+        .iter()
+        .flatten()
+        .next()
+        .map(|first| first.iter().map(|(question, _)| *question).collect())
+        .unwrap_or_default();
+
+    let mut tests: Vec<SubgroupImpactTestData> = questions
+        .iter()
+        .enumerate()
+        .filter_map(|(question_index, question)| {
+            let groups: Vec<Vec<f64>> = ratings
+                .iter()
+                .map(|subset| {
+                    subset
+                        .iter()
+                        .filter_map(|r| r[question_index].1)
+                        .map(|rating| rating as f64)
+                        .collect()
+                })
+                .collect();
+
+            let (h_statistic, df, p_value) = get_kruskal_wallis(&groups)?;
+
+            Some(SubgroupImpactTestData {
+                question: (*question).to_owned(),
+                question_info: get_question_info(question),
+                group_count: df + 1,
+                h_statistic: round_value(h_statistic),
+                df,
+                p_value: round_value(p_value),
+            })
+        })
+        .collect();
+
+    tests.sort_by(|a, b| a.p_value.partial_cmp(&b.p_value).unwrap());
+    tests
+}
@@ -0,0 +1,278 @@
+use crate::{
+    fns::{html::escape_html, question::get_question_label, util::get_two_sided_p_value},
+    util::{
+        types::{ImpactDistributionData, QuestionCorrelationData, QuestionDataDiffs},
+        vars,
+    },
+};
+use std::fmt::Write;
+
+static CHART_WIDTH: f64 = 720f64;
+static CHART_MARGIN: f64 = 40f64;
+static LABEL_WIDTH: f64 = 320f64;
+static ROW_HEIGHT: f64 = 18f64;
+static LIKERT_COLOURS: [&str; 5] = ["#ca0020", "#f4a582", "#cccccc", "#92c5de", "#0571b0"];
+
+/// Scatter plot of rho against the impact average, split into quadrants by the given thresholds.
+pub fn get_scatter_plot(
+    diffs: &[QuestionDataDiffs],
+    rho_threshold: f64,
+    impact_threshold: f64,
+) -> String {
+    let size = CHART_WIDTH - 2f64 * CHART_MARGIN;
+    let impact_min = vars::IMPACT_RATING_MIN as f64;
+    let impact_max = vars::IMPACT_RATING_MAX as f64;
+    let get_x = |rho: f64| CHART_MARGIN + (rho + 1f64) / 2f64 * size;
+    let get_y =
+        |impact: f64| CHART_MARGIN + (impact_max - impact) / (impact_max - impact_min) * size;
+
+    let mut svg = get_svg_start(CHART_WIDTH, CHART_WIDTH);
+    write_axis_line(
+        &mut svg,
+        get_x(-1f64),
+        get_y(impact_min),
+        get_x(1f64),
+        get_y(impact_min),
+    );
+    write_axis_line(
+        &mut svg,
+        get_x(-1f64),
+        get_y(impact_min),
+        get_x(-1f64),
+        get_y(impact_max),
+    );
+    write_threshold_line(
+        &mut svg,
+        get_x(rho_threshold),
+        get_y(impact_min),
+        get_x(rho_threshold),
+        get_y(impact_max),
+    );
+    write_threshold_line(
+        &mut svg,
+        get_x(-1f64),
+        get_y(impact_threshold),
+        get_x(1f64),
+        get_y(impact_threshold),
+    );
+
+    for tick in [-1f64, -0.5, 0f64, 0.5, 1f64] {
+        write_text(
+            &mut svg,
+            get_x(tick),
+            get_y(impact_min) + 16f64,
+            "middle",
+            &tick.to_string(),
+        );
+    }
+    for tick in vars::IMPACT_RATING_MIN..=vars::IMPACT_RATING_MAX {
+        write_text(
+            &mut svg,
+            get_x(-1f64) - 8f64,
+            get_y(tick as f64) + 4f64,
+            "end",
+            &tick.to_string(),
+        );
+    }
+    write_text(
+        &mut svg,
+        CHART_WIDTH / 2f64,
+        CHART_WIDTH - 6f64,
+        "middle",
+        "Spearman's rho with quality",
+    );
+    write_text(
+        &mut svg,
+        12f64,
+        CHART_MARGIN - 12f64,
+        "start",
+        "Impact average",
+    );
+
+    for diff in diffs {
+        let _ = write!(
+            svg,
+            r#"<circle cx="{:.1}" cy="{:.1}" r="4" fill="{}"><title>{} (rho {}, impact {})</title></circle>"#,
+            get_x(diff.rho),
+            get_y(diff.impact_average as f64),
+            if get_two_sided_p_value(diff.p_value) < vars::SIGNIFICANCE_LEVEL {
+                "#0571b0"
+            } else {
+                "#999999"
+            },
+            escape_html(get_question_label(&diff.question_correlation)),
+            diff.rho,
+            diff.impact_average
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Forest plot with one row per question: rho as a point and its confidence interval as a line.
+pub fn get_forest_plot(correlations: &[QuestionCorrelationData]) -> String {
+    let plot_width = CHART_WIDTH - LABEL_WIDTH - CHART_MARGIN;
+    let height = correlations.len() as f64 * ROW_HEIGHT + 2f64 * CHART_MARGIN;
+    let get_x = |rho: f64| LABEL_WIDTH + (rho + 1f64) / 2f64 * plot_width;
+    let bottom = height - CHART_MARGIN;
+
+    let mut svg = get_svg_start(CHART_WIDTH, height);
+    write_axis_line(&mut svg, get_x(-1f64), bottom, get_x(1f64), bottom);
+    write_threshold_line(&mut svg, get_x(0f64), CHART_MARGIN, get_x(0f64), bottom);
+    for tick in [-1f64, -0.5, 0f64, 0.5, 1f64] {
+        write_text(
+            &mut svg,
+            get_x(tick),
+            bottom + 16f64,
+            "middle",
+            &tick.to_string(),
+        );
+    }
+
+    for (i, correlation) in correlations.iter().enumerate() {
+        let y = CHART_MARGIN + (i as f64 + 0.5) * ROW_HEIGHT;
+        write_text(
+            &mut svg,
+            LABEL_WIDTH - 8f64,
+            y + 4f64,
+            "end",
+            get_question_label(&correlation.question),
+        );
+        let _ = write!(
+            svg,
+            r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#333333"/><rect x="{:.1}" y="{:.1}" width="6" height="6" fill="#0571b0"><title>rho {} [{}, {}]</title></rect>"##,
+            get_x(correlation.rho_ci_lower),
+            y,
+            get_x(correlation.rho_ci_upper),
+            y,
+            get_x(correlation.rho) - 3f64,
+            y - 3f64,
+            correlation.rho,
+            correlation.rho_ci_lower,
+            correlation.rho_ci_upper
+        );
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Diverging stacked bars of the impact ratings, centred on the middle of the neutral rating.
+pub fn get_likert_chart(distributions: &[ImpactDistributionData]) -> String {
+    let plot_width = CHART_WIDTH - LABEL_WIDTH - CHART_MARGIN;
+    let centre = LABEL_WIDTH + plot_width / 2f64;
+    let height = distributions.len() as f64 * ROW_HEIGHT + 2f64 * CHART_MARGIN;
+
+    let mut svg = get_svg_start(CHART_WIDTH, height);
+    write_threshold_line(
+        &mut svg,
+        centre,
+        CHART_MARGIN,
+        centre,
+        height - CHART_MARGIN,
+    );
+    write_text(
+        &mut svg,
+        LABEL_WIDTH,
+        height - CHART_MARGIN + 16f64,
+        "middle",
+        "100%",
+    );
+    write_text(
+        &mut svg,
+        centre,
+        height - CHART_MARGIN + 16f64,
+        "middle",
+        "0%",
+    );
+    write_text(
+        &mut svg,
+        LABEL_WIDTH + plot_width,
+        height - CHART_MARGIN + 16f64,
+        "middle",
+        "100%",
+    );
+
+    for (i, distribution) in distributions.iter().enumerate() {
+        let y = CHART_MARGIN + i as f64 * ROW_HEIGHT;
+        write_text(
+            &mut svg,
+            LABEL_WIDTH - 8f64,
+            y + ROW_HEIGHT / 2f64 + 4f64,
+            "end",
+            get_question_label(&distribution.question),
+        );
+
+        let total: usize = distribution.counts.iter().sum();
+        if total == 0 {
+            continue;
+        }
+
+        let widths: Vec<f64> = distribution
+            .counts
+            .iter()
+            .map(|count| *count as f64 / total as f64 * plot_width / 2f64)
+            .collect();
+        let middle = widths.len() / 2;
+        let negative_width: f64 = widths[..middle].iter().sum::<f64>()
+            + if widths.len() % 2 == 1 {
+                widths[middle] / 2f64
+            } else {
+                0f64
+            };
+
+        let mut x = centre - negative_width;
+        for (j, width) in widths.iter().enumerate() {
+            let _ = write!(
+                svg,
+                r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"><title>{}: {}</title></rect>"#,
+                x,
+                y + 2f64,
+                width,
+                ROW_HEIGHT - 4f64,
+                LIKERT_COLOURS[j % LIKERT_COLOURS.len()],
+                distribution.ratings[j],
+                distribution.counts[j]
+            );
+            x += width;
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+fn get_svg_start(width: f64, height: f64) -> String {
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" font-family="sans-serif" font-size="11">"#,
+        width, height
+    )
+}
+
+fn write_axis_line(svg: &mut String, x1: f64, y1: f64, x2: f64, y2: f64) {
+    let _ = write!(
+        svg,
+        r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#333333"/>"##,
+        x1, y1, x2, y2
+    );
+}
+
+fn write_threshold_line(svg: &mut String, x1: f64, y1: f64, x2: f64, y2: f64) {
+    let _ = write!(
+        svg,
+        r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#999999" stroke-dasharray="4 4"/>"##,
+        x1, y1, x2, y2
+    );
+}
+
+fn write_text(svg: &mut String, x: f64, y: f64, anchor: &str, text: &str) {
+    let _ = write!(
+        svg,
+        r#"<text x="{:.1}" y="{:.1}" text-anchor="{}">{}</text>"#,
+        x,
+        y,
+        anchor,
+        escape_html(text)
+    );
+}
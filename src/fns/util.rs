@@ -1,15 +1,71 @@
 use crate::util::{
-    types::Feedback,
+    types::{Feedback, MissingReason},
     vars::{self},
 };
 
+#[allow(clippy::ptr_arg)]
 pub fn assign_ids(feedbacks: &mut Vec<Feedback>) {
     for (i, feedback) in feedbacks.iter_mut().enumerate() {
         feedback.id = Some(i);
     }
 }
 
+pub fn round_value(value: f64) -> f64 {
+    (value * vars::ROUND_DECIMAL_PLACES_MULTIPLIER as f64).round()
+        / vars::ROUND_DECIMAL_PLACES_MULTIPLIER as f64
+}
+
+/// Formats a number for reports with `precision` decimal places.
+pub fn format_value(precision: usize, value: f64) -> String {
+    format!("{:.*}", precision, value)
+}
+
+/// Confidence level of the intervals in percent, e.g. 95 for a significance level of 0.05.
+pub fn get_confidence_percentage() -> f64 {
+    round_value(100f64 * (1f64 - vars::SIGNIFICANCE_LEVEL))
+}
+
+/// The two-sided p-value of a one-sided one, which the reports show next to the two-sided
+/// confidence intervals.
+pub fn get_two_sided_p_value(p_value: f64) -> f64 {
+    (2f64 * p_value).min(1f64)
+}
+
+/// Returns `*`, `**` or `***` for p below 0.05, 0.01 or 0.001 respectively.
+pub fn get_significance_marker(p_value: f64) -> &'static str {
+    vars::SIGNIFICANCE_MARKERS
+        .iter()
+        .find(|(threshold, _)| p_value < *threshold)
+        .map(|(_, marker)| *marker)
+        .unwrap_or("")
+}
+
+/// Whether a scored answer counts as adopting the practice, i.e. following it fully or
+/// partially.
+pub const fn is_adopted(score: i8) -> bool {
+    score >= vars::WEIGHTING_PARTIAL
+}
+
+/// Why an answer has no score; `None` for answers with a weighting.
+pub fn get_missing_reason(answer: &str) -> Option<MissingReason> {
+    match answer {
+        "I don't know (e.g. you don't know how your team members use generative AI tools)" => {
+            Some(MissingReason::DontKnow)
+        }
+        "I don't know" => Some(MissingReason::DontKnow),
+        "AI tools were not used to generate code" => Some(MissingReason::NotUsed),
+        "Regex patterns were not used" => Some(MissingReason::NotUsed),
+        "Not applicable" => Some(MissingReason::NotApplicable),
+        _ => None,
+    }
+}
+
+#[allow(clippy::needless_return)]
 pub fn get_answer_score(answer: &str) -> i8 {
+    if get_missing_reason(answer).is_some() {
+        return vars::WEIGHTING_NOT_APPLICABLE;
+    }
+
     // The weightings were werified to be valid for each question despite overlaps in answers.
     return match answer {
         "Yes, with major refactoring done in separate branches" => vars::WEIGHTING_FULL,
@@ -44,13 +100,6 @@ pub fn get_answer_score(answer: &str) -> i8 {
 
         "Yes, another language was used to some degree" => vars::WEIGHTING_PARTIAL_NEG,
 
-        "I don't know (e.g. you don't know how your team members use generative AI tools)" => {
-            vars::WEIGHTING_NOT_APPLICABLE
-        }
-        "AI tools were not used to generate code" => vars::WEIGHTING_NOT_APPLICABLE,
-        "Regex patterns were not used" => vars::WEIGHTING_NOT_APPLICABLE,
-        "Not applicable" => vars::WEIGHTING_NOT_APPLICABLE,
-        "I don't know" => vars::WEIGHTING_NOT_APPLICABLE,
         _ => panic!("Invalid answer: {}", answer),
     };
 }
@@ -0,0 +1,179 @@
+use crate::{
+    fns::{
+        impact::{get_impact_averages, get_impact_ratings},
+        question::{get_paired_field_name, get_question_info},
+        rank::calculate_rankings,
+        score::get_score_columns,
+        stats::{get_correlation_difference, get_mann_whitney, get_proportion_difference},
+        util::{is_adopted, round_value},
+    },
+    util::{
+        types::{
+            Feedback, QuestionCorrelationData, QuestionImpactData, QuestionWaveComparisonData,
+            WaveComparisonData,
+        },
+        vars,
+    },
+};
+use std::collections::HashMap;
+
+struct WaveData {
+    respondent_count: usize,
+    correlations: Vec<QuestionCorrelationData>,
+    impacts: Vec<QuestionImpactData>,
+    impact_ratings: HashMap<&'static str, Vec<f64>>,
+    adoption_counts: HashMap<&'static str, (usize, usize)>,
+    questions: Vec<&'static str>,
+}
+
+/// Compares each wave with the one before it, in the order the waves were given.
+/// A practice counts as adopted when it is followed fully or partially, see `is_adopted`.
+pub fn calculate_wave_comparisons(
+    feedbacks: &[Feedback],
+    waves: &[String],
+) -> Result<Vec<WaveComparisonData>, String> {
+    if waves.len() < 2 {
+        return Err(format!(
+            "Comparing waves needs at least two waves, got {}",
+            waves.len()
+        ));
+    }
+
+    let wave_data: Vec<WaveData> = waves
+        .iter()
+        .map(|wave| {
+            let subset: Vec<Feedback> = feedbacks
+                .iter()
+                .filter(|f| f.origin.wave == *wave)
+                .cloned()
+                .collect();
+            get_wave_data(&subset)
+        })
+        .collect();
+
+    let comparisons = waves
+        .windows(2)
+        .zip(wave_data.windows(2))
+        .map(|(labels, data)| {
+            let (before, after) = (&data[0], &data[1]);
+
+            WaveComparisonData {
+                wave_before: labels[0].to_owned(),
+                wave_after: labels[1].to_owned(),
+                respondent_count_before: before.respondent_count,
+                respondent_count_after: after.respondent_count,
+                questions: before
+                    .questions
+                    .iter()
+                    .map(|question| get_question_comparison(question, before, after))
+                    .collect(),
+            }
+        })
+        .collect();
+    Ok(comparisons)
+}
+
+fn get_wave_data(feedbacks: &Vec<Feedback>) -> WaveData {
+    let mut impact_ratings: HashMap<&'static str, Vec<f64>> = HashMap::new();
+    let mut adoption_counts: HashMap<&'static str, (usize, usize)> = HashMap::new();
+    let mut questions: Vec<&'static str> = Vec::new();
+
+    for feedback in feedbacks {
+        for (question, rating) in get_impact_ratings(feedback) {
+            let ratings = impact_ratings.entry(question).or_default();
+            if let Some(r) = rating {
+                ratings.push(r as f64);
+            }
+        }
+    }
+
+    for column in get_score_columns(feedbacks).questions {
+        let counts = adoption_counts.entry(column.question).or_insert((0, 0));
+        for score in column.scores {
+            if score != vars::WEIGHTING_NOT_APPLICABLE {
+                counts.1 += 1;
+                if is_adopted(score) {
+                    counts.0 += 1;
+                }
+            }
+        }
+        questions.push(column.question);
+    }
+
+    WaveData {
+        respondent_count: feedbacks.len(),
+        correlations: calculate_rankings(feedbacks),
+        impacts: get_impact_averages(feedbacks),
+        impact_ratings,
+        adoption_counts,
+        questions,
+    }
+}
+
+fn get_question_comparison(
+    question: &str,
+    before: &WaveData,
+    after: &WaveData,
+) -> QuestionWaveComparisonData {
+    let question_impact = get_paired_field_name(question).unwrap_or_default();
+
+    let correlation_before = before.correlations.iter().find(|c| c.question == question);
+    let correlation_after = after.correlations.iter().find(|c| c.question == question);
+    let rho_test = correlation_before
+        .zip(correlation_after)
+        .and_then(|(b, a)| {
+            get_correlation_difference((b.rho, b.answer_count), (a.rho, a.answer_count))
+        });
+
+    let impact_before = before
+        .impacts
+        .iter()
+        .find(|i| i.question == question_impact);
+    let impact_after = after.impacts.iter().find(|i| i.question == question_impact);
+    let impact_test = before
+        .impact_ratings
+        .get(question_impact)
+        .zip(after.impact_ratings.get(question_impact))
+        .and_then(|(b, a)| get_mann_whitney(b, a));
+
+    let adoption_before = before.adoption_counts.get(question).copied();
+    let adoption_after = after.adoption_counts.get(question).copied();
+    let get_rate = |counts: Option<(usize, usize)>| {
+        counts
+            .filter(|(_, applicable)| *applicable > 0)
+            .map(|(adopted, applicable)| adopted as f64 / applicable as f64)
+    };
+    let adoption_rate_before = get_rate(adoption_before);
+    let adoption_rate_after = get_rate(adoption_after);
+    let adoption_test = adoption_before
+        .zip(adoption_after)
+        .and_then(|(b, a)| get_proportion_difference(b, a));
+
+    QuestionWaveComparisonData {
+        question: question.to_owned(),
+        question_info: get_question_info(question),
+        question_impact: question_impact.to_owned(),
+        rho_before: correlation_before.map(|c| c.rho),
+        rho_after: correlation_after.map(|c| c.rho),
+        rho_change: correlation_before
+            .zip(correlation_after)
+            .map(|(b, a)| round_value(a.rho - b.rho)),
+        rho_z: rho_test.map(|(z, _)| round_value(z)),
+        rho_p_value: rho_test.map(|(_, p)| round_value(p)),
+        impact_average_before: impact_before.map(|i| i.impact_average),
+        impact_average_after: impact_after.map(|i| i.impact_average),
+        impact_average_change: impact_before
+            .zip(impact_after)
+            .map(|(b, a)| round_value((a.impact_average - b.impact_average) as f64) as f32),
+        impact_mann_whitney_u: impact_test.map(|(u, _, _)| round_value(u)),
+        impact_z: impact_test.map(|(_, z, _)| round_value(z)),
+        impact_p_value: impact_test.map(|(_, _, p)| round_value(p)),
+        adoption_rate_before: adoption_rate_before.map(round_value),
+        adoption_rate_after: adoption_rate_after.map(round_value),
+        adoption_rate_change: adoption_rate_before
+            .zip(adoption_rate_after)
+            .map(|(b, a)| round_value(a - b)),
+        adoption_z: adoption_test.map(|(z, _)| round_value(z)),
+        adoption_p_value: adoption_test.map(|(_, p)| round_value(p)),
+    }
+}
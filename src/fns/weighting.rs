@@ -0,0 +1,200 @@
+use crate::{
+    fns::{
+        rank::{adjust_p_values, get_correlations, get_pearson_correlation},
+        score::get_weighted_score_columns,
+        util::{is_adopted, round_value},
+    },
+    util::{
+        types::{
+            Feedback, QuestionCorrelationData, QuestionWeightingSensitivityData,
+            SchemeCorrelationData, ScoreColumns, WeightingScheme, WeightingSchemeSummaryData,
+            WeightingSensitivityData,
+        },
+        vars,
+    },
+};
+
+/// Scorings of the answer weightings, the standard weightings first. Spearman's rho only
+/// depends on the order of the scores, so a scheme moves rho only where it merges or drops
+/// levels. Pearson's r also depends on the spacing of the scores, which is all that
+/// `equal_interval` changes.
+pub static WEIGHTING_SCHEMES: [WeightingScheme; 5] = [
+    WeightingScheme {
+        name: "standard",
+        full: vars::WEIGHTING_FULL,
+        partial: vars::WEIGHTING_PARTIAL,
+        partial_neg: vars::WEIGHTING_PARTIAL_NEG,
+        full_neg: vars::WEIGHTING_FULL_NEG,
+    },
+    // Adopted or not, as in the wave comparisons
+    WeightingScheme {
+        name: "binary",
+        full: get_adoption_score(vars::WEIGHTING_FULL),
+        partial: get_adoption_score(vars::WEIGHTING_PARTIAL),
+        partial_neg: get_adoption_score(vars::WEIGHTING_PARTIAL_NEG),
+        full_neg: get_adoption_score(vars::WEIGHTING_FULL_NEG),
+    },
+    WeightingScheme {
+        name: "equal_interval",
+        full: 3,
+        partial: 1,
+        partial_neg: -1,
+        full_neg: -3,
+    },
+    WeightingScheme {
+        name: "partial_as_full",
+        full: 2,
+        partial: 2,
+        partial_neg: -2,
+        full_neg: -2,
+    },
+    // Partial answers count as not applicable
+    WeightingScheme {
+        name: "partial_excluded",
+        full: 2,
+        partial: vars::WEIGHTING_NOT_APPLICABLE,
+        partial_neg: vars::WEIGHTING_NOT_APPLICABLE,
+        full_neg: -2,
+    },
+];
+
+const fn get_adoption_score(score: i8) -> i8 {
+    if is_adopted(score) { 1 } else { -1 }
+}
+
+/// Reruns the correlations of every practice question under each weighting scheme and
+/// reports how far rho, Pearson's r and significance move from the standard weightings.
+pub fn calculate_weighting_sensitivity(feedbacks: &[Feedback]) -> WeightingSensitivityData {
+    let (scheme_correlations, scheme_linear_correlations): (
+        Vec<Vec<QuestionCorrelationData>>,
+        Vec<Vec<f64>>,
+    ) = WEIGHTING_SCHEMES
+        .iter()
+        .map(|scheme| {
+            let columns = get_weighted_score_columns(feedbacks, scheme);
+            let mut correlations = get_correlations(&columns);
+            adjust_p_values(&mut correlations);
+            let linear_correlations = get_linear_correlations(&columns, &correlations);
+            (correlations, linear_correlations)
+        })
+        .unzip();
+
+    let questions: Vec<QuestionWeightingSensitivityData> = scheme_correlations[0]
+        .iter()
+        .zip(scheme_linear_correlations[0].iter())
+        .map(|(standard, standard_r)| {
+            let is_significant = standard.p_value < vars::SIGNIFICANCE_LEVEL;
+            let schemes: Vec<SchemeCorrelationData> = WEIGHTING_SCHEMES
+                .iter()
+                .zip(scheme_correlations.iter())
+                .zip(scheme_linear_correlations.iter())
+                .map(|((scheme, correlations), linear_correlations)| {
+                    let index = correlations
+                        .iter()
+                        .position(|c| c.question == standard.question);
+                    let correlation = index.map(|i| &correlations[i]);
+                    let r = index.map(|i| linear_correlations[i]);
+
+                    SchemeCorrelationData {
+                        scheme: scheme.name.to_owned(),
+                        rho: correlation.map(|c| c.rho),
+                        p_value: correlation.map(|c| c.p_value),
+                        p_value_adjusted: correlation.map(|c| c.p_value_adjusted),
+                        answer_count: correlation.map(|c| c.answer_count),
+                        rho_change: correlation.map(|c| round_value(c.rho - standard.rho)),
+                        r,
+                        r_change: r.map(|r| round_value(r - standard_r)),
+                        is_significance_changed: correlation.map_or(is_significant, |c| {
+                            (c.p_value < vars::SIGNIFICANCE_LEVEL) != is_significant
+                        }),
+                    }
+                })
+                .collect();
+
+            let rhos = || schemes.iter().filter_map(|s| s.rho);
+            let rs = || schemes.iter().filter_map(|s| s.r);
+
+            QuestionWeightingSensitivityData {
+                question: standard.question.to_owned(),
+                question_info: standard.question_info.clone(),
+                rho: standard.rho,
+                p_value: standard.p_value,
+                rho_min: rhos().fold(standard.rho, f64::min),
+                rho_max: rhos().fold(standard.rho, f64::max),
+                r: *standard_r,
+                r_min: rs().fold(*standard_r, f64::min),
+                r_max: rs().fold(*standard_r, f64::max),
+                is_robust: !schemes.iter().any(|s| s.is_significance_changed),
+                schemes,
+            }
+        })
+        .collect();
+
+    let schemes: Vec<WeightingSchemeSummaryData> = WEIGHTING_SCHEMES
+        .iter()
+        .zip(scheme_correlations.iter())
+        .enumerate()
+        .map(|(index, (scheme, correlations))| {
+            let rho_changes: Vec<f64> = questions
+                .iter()
+                .filter_map(|q| q.schemes[index].rho_change)
+                .map(f64::abs)
+                .collect();
+            let r_changes: Vec<f64> = questions
+                .iter()
+                .filter_map(|q| q.schemes[index].r_change)
+                .map(f64::abs)
+                .collect();
+
+            WeightingSchemeSummaryData {
+                scheme: scheme.name.to_owned(),
+                full: scheme.full,
+                partial: scheme.partial,
+                partial_neg: scheme.partial_neg,
+                full_neg: scheme.full_neg,
+                significant_count: correlations
+                    .iter()
+                    .filter(|c| c.p_value < vars::SIGNIFICANCE_LEVEL)
+                    .count(),
+                significance_changed_count: questions
+                    .iter()
+                    .filter(|q| q.schemes[index].is_significance_changed)
+                    .count(),
+                mean_absolute_rho_change: get_mean_change(&rho_changes),
+                max_absolute_rho_change: rho_changes.iter().copied().fold(0f64, f64::max),
+                mean_absolute_r_change: get_mean_change(&r_changes),
+                max_absolute_r_change: r_changes.iter().copied().fold(0f64, f64::max),
+            }
+        })
+        .collect();
+
+    WeightingSensitivityData { schemes, questions }
+}
+
+/// Pearson's r of the rescored applicable answers with the quality estimate, for each
+/// question of `correlations` in the same order.
+fn get_linear_correlations(
+    columns: &ScoreColumns,
+    correlations: &[QuestionCorrelationData],
+) -> Vec<f64> {
+    correlations
+        .iter()
+        .map(|correlation| {
+            let column = columns
+                .questions
+                .iter()
+                .find(|c| c.question == correlation.question)
+                .unwrap();
+            let (scores, qualities) = columns.get_applicable_scores(column);
+            round_value(get_pearson_correlation(&scores, &qualities))
+        })
+        .collect()
+}
+
+fn get_mean_change(changes: &[f64]) -> f64 {
+    if changes.is_empty() {
+        0f64
+    } else {
+        round_value(changes.iter().sum::<f64>() / changes.len() as f64)
+    }
+}
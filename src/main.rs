@@ -1,37 +1,292 @@
 mod fns;
 mod util;
 
+#[cfg(test)]
+mod tests;
+
 use crate::fns::csv::read_csv;
 use fns::{
-    diff::calculate_diffs,
-    fs::{write_correlation_data, write_diffs_data, write_impact_average, write_impact_data},
-    impact::{get_impact_average, get_impact_averages},
-    rank::calculate_rankings,
+    cluster::calculate_clusters,
+    compare::compare_results,
+    diff::{calculate_diffs, get_unrated_questions},
+    export::get_respondent_scores,
+    factor::calculate_factor_analysis,
+    frequency::calculate_frequencies,
+    fs::{
+        read_comparable_results, write_cluster_data, write_correlation_data, write_diffs_data,
+        write_factor_analysis_data, write_frequency_data, write_html_report, write_impact_average,
+        write_impact_data, write_influence_data, write_latex_tables, write_markdown_report,
+        write_missing_data, write_optimal_scaling_data, write_ordinal_regression_data,
+        write_power_analysis_data, write_respondent_scores, write_result_comparison_data,
+        write_results_data, write_simulated_survey, write_skipped_question_data,
+        write_subgroup_data, write_wave_comparison_data, write_weighting_sensitivity_data,
+    },
+    html::get_html_report,
+    impact::{get_impact_average, get_impact_averages, get_impact_distributions},
+    influence::calculate_influences,
+    latex::get_latex_tables,
+    markdown::get_markdown_report,
+    missing::calculate_missing_data,
+    ordinal::calculate_ordinal_regressions,
+    power::calculate_power_analysis,
+    rank::calculate_strategy_rankings,
+    results::get_run_metadata,
+    scaling::calculate_optimal_scalings,
+    simulate::simulate_survey,
+    subgroup::calculate_subgroup_analyses,
     util::assign_ids,
+    wave::calculate_wave_comparisons,
+    weighting::calculate_weighting_sensitivity,
 };
 use std::process;
-use util::types::{QuestionCorrelationData, QuestionDataDiffs, QuestionImpactData};
+use util::args::{Args, Mode, ReportFormat, get_args};
+use util::types::{
+    ClusterAnalysisData, FactorAnalysisData, Feedback, ImpactDistributionData, MissingDataStrategy,
+    PowerAnalysisData, QuestionCorrelationData, QuestionDataDiffs, QuestionFrequencyData,
+    QuestionImpactData, QuestionInfluenceData, QuestionMissingData, QuestionOptimalScalingData,
+    QuestionOrdinalRegressionData, RespondentScoreData, ResultComparisonData, ResultsData,
+    RunMetadata, SkippedQuestionData, SubgroupAnalysisData, WaveComparisonData,
+    WeightingSensitivityData,
+};
 
 fn main() {
-    let filename = "./data/feedbacks.csv";
+    let args = get_args();
 
-    match read_csv(filename) {
+    match args.mode {
+        Mode::Analyse => run_analysis(&read_feedbacks(&args), &args),
+        Mode::CompareWaves => {
+            let comparisons: Vec<WaveComparisonData> =
+                match calculate_wave_comparisons(&read_feedbacks(&args), &args.get_waves()) {
+                    Ok(comparisons) => comparisons,
+                    Err(err) => {
+                        println!("Failed to compare waves: {}", err);
+                        process::exit(1);
+                    }
+                };
+            write_wave_comparison_data(&comparisons, args.output_format);
+        }
+        Mode::Report => run_report(&read_feedbacks(&args), &args),
+        Mode::Power => {
+            let power_analysis: PowerAnalysisData = calculate_power_analysis(
+                &read_feedbacks(&args),
+                &args.effect_sizes,
+                args.target_power,
+                args.simulation_count,
+            );
+            write_power_analysis_data(&power_analysis, args.output_format);
+        }
+        Mode::Sensitivity => {
+            // The schemes rescore the observed answers, which the other strategies replace
+            if !matches!(args.missing_data, MissingDataStrategy::Pairwise) {
+                println!("Weighting sensitivity only supports --missing-data pairwise");
+                process::exit(1);
+            }
+
+            let sensitivity: WeightingSensitivityData =
+                calculate_weighting_sensitivity(&read_feedbacks(&args));
+            write_weighting_sensitivity_data(&sensitivity, args.output_format);
+        }
+        Mode::Export => {
+            let scores: Vec<RespondentScoreData> =
+                get_respondent_scores(&read_feedbacks(&args), args.tie_method);
+            write_respondent_scores(&scores, args.export_format);
+        }
+        Mode::Compare => run_comparison(&args),
+        Mode::Simulate => run_simulation(&args),
+    }
+}
+
+/// Reads the survey responses of every input file and numbers the respondents, or exits.
+fn read_feedbacks(args: &Args) -> Vec<Feedback> {
+    match read_csv(&args.inputs, &args.group_columns) {
         Ok(mut feedbacks) => {
             assign_ids(&mut feedbacks);
+            feedbacks
+        }
+        Err(err) => {
+            println!("Failed to read CSV: {}", err);
+            process::exit(1);
+        }
+    }
+}
+
+fn run_analysis(feedbacks: &Vec<Feedback>, args: &Args) {
+    let metadata: RunMetadata = match get_run_metadata(args, feedbacks) {
+        Ok(metadata) => metadata,
+        Err(err) => {
+            println!("Failed to record run metadata: {}", err);
+            process::exit(1);
+        }
+    };
+
+    let (rankings, mut skipped_questions): (
+        Vec<QuestionCorrelationData>,
+        Vec<SkippedQuestionData>,
+    ) = match calculate_strategy_rankings(feedbacks, &args.missing_data) {
+        Ok(rankings) => rankings,
+        Err(err) => {
+            println!("Failed to handle missing data: {}", err);
+            process::exit(1);
+        }
+    };
+    let impact_averages: Vec<QuestionImpactData> = get_impact_averages(feedbacks);
+    let impact_average: f64 = get_impact_average(&impact_averages);
+    let diffs: Vec<QuestionDataDiffs> = calculate_diffs(&rankings, &impact_averages);
+    skipped_questions.extend(get_unrated_questions(&rankings, &impact_averages));
+    print_skipped_questions(&skipped_questions);
+    let frequencies: Vec<QuestionFrequencyData> = calculate_frequencies(feedbacks);
+    let ordinal_regressions: Vec<QuestionOrdinalRegressionData> =
+        calculate_ordinal_regressions(feedbacks);
+    let factor_analysis: FactorAnalysisData = calculate_factor_analysis(feedbacks);
+    let clusters: Vec<ClusterAnalysisData> = calculate_clusters(feedbacks);
+    let influences: Vec<QuestionInfluenceData> = calculate_influences(feedbacks);
+    let missing_data: Vec<QuestionMissingData> =
+        calculate_missing_data(feedbacks, args.test_dont_know);
 
-            let rankings: Vec<QuestionCorrelationData> = calculate_rankings(&feedbacks);
-            let impact_averages: Vec<QuestionImpactData> = get_impact_averages(&feedbacks);
-            let impact_average: f64 = get_impact_average(&impact_averages);
-            let diffs: Vec<QuestionDataDiffs> = calculate_diffs(&rankings, &impact_averages);
+    write_correlation_data(&rankings, args.output_format);
+    write_impact_data(&impact_averages, args.output_format);
+    write_impact_average(impact_average, args.output_format);
+    write_diffs_data(&diffs, args.output_format);
+    write_frequency_data(&frequencies, args.output_format);
+    write_ordinal_regression_data(&ordinal_regressions, args.output_format);
+    write_factor_analysis_data(&factor_analysis, args.output_format);
+    write_cluster_data(&clusters, args.output_format);
+    write_influence_data(&influences, args.output_format);
+    write_missing_data(&missing_data, args.output_format);
+    write_skipped_question_data(&skipped_questions, args.output_format);
 
-            write_correlation_data(&rankings);
-            write_impact_data(&impact_averages);
-            write_impact_average(impact_average);
-            write_diffs_data(&diffs);
+    let optimal_scalings: Option<Vec<QuestionOptimalScalingData>> = if args.optimal_scaling {
+        let optimal_scalings = calculate_optimal_scalings(feedbacks);
+        write_optimal_scaling_data(&optimal_scalings, args.output_format);
+        Some(optimal_scalings)
+    } else {
+        None
+    };
+
+    let subgroups: Option<Vec<SubgroupAnalysisData>> = if args.group_columns.is_empty() {
+        None
+    } else {
+        let subgroups = calculate_subgroup_analyses(feedbacks, &args.group_columns);
+        write_subgroup_data(&subgroups, args.output_format);
+        Some(subgroups)
+    };
+
+    write_results_data(&ResultsData {
+        metadata,
+        correlations: rankings,
+        impacts: impact_averages,
+        impact_average,
+        diffs,
+        frequencies,
+        ordinal_regressions,
+        factor_analysis,
+        clusters,
+        influences,
+        missing_data,
+        optimal_scalings,
+        subgroups,
+        skipped_questions,
+    });
+}
+
+fn run_report(feedbacks: &Vec<Feedback>, args: &Args) {
+    let (rankings, mut skipped_questions): (
+        Vec<QuestionCorrelationData>,
+        Vec<SkippedQuestionData>,
+    ) = match calculate_strategy_rankings(feedbacks, &args.missing_data) {
+        Ok(rankings) => rankings,
+        Err(err) => {
+            println!("Failed to handle missing data: {}", err);
+            process::exit(1);
+        }
+    };
+    let impact_averages: Vec<QuestionImpactData> = get_impact_averages(feedbacks);
+    let impact_average: f64 = get_impact_average(&impact_averages);
+    let diffs: Vec<QuestionDataDiffs> = calculate_diffs(&rankings, &impact_averages);
+    skipped_questions.extend(get_unrated_questions(&rankings, &impact_averages));
+    print_skipped_questions(&skipped_questions);
+    let distributions: Vec<ImpactDistributionData> = get_impact_distributions(feedbacks);
+    let frequencies: Vec<QuestionFrequencyData> = calculate_frequencies(feedbacks);
+
+    for format in args.report_formats.iter() {
+        match format {
+            ReportFormat::Html => write_html_report(&get_html_report(
+                &rankings,
+                &impact_averages,
+                impact_average,
+                &diffs,
+                &distributions,
+                &frequencies,
+                args.precision,
+            )),
+            ReportFormat::Markdown => write_markdown_report(&get_markdown_report(
+                args,
+                feedbacks.len(),
+                &rankings,
+                &impact_averages,
+                impact_average,
+                &diffs,
+                &frequencies,
+            )),
+            ReportFormat::Latex => {
+                write_latex_tables(&get_latex_tables(args, &rankings, &impact_averages, &diffs))
+            }
+        }
+    }
+}
+
+fn run_comparison(args: &Args) {
+    let (before_path, after_path) = (&args.compare_paths[0], &args.compare_paths[1]);
+    let (before, after) = match (
+        read_comparable_results(before_path),
+        read_comparable_results(after_path),
+    ) {
+        (Ok(before), Ok(after)) => (before, after),
+        (Err(err), _) | (_, Err(err)) => {
+            println!("Failed to read results: {}", err);
+            process::exit(1);
+        }
+    };
+
+    let comparison: ResultComparisonData =
+        compare_results(&before, &after, before_path, after_path, args.tolerance);
+    write_result_comparison_data(&comparison, args.output_format);
+
+    for question in comparison.questions.iter().filter(|q| q.is_changed) {
+        println!("Changed: {}", question.question);
+    }
+    println!(
+        "{} of {} questions changed",
+        comparison.changed_question_count,
+        comparison.questions.len()
+    );
+
+    if args.tolerance.is_some() && comparison.changed_question_count > 0 {
+        process::exit(2);
+    }
+}
+
+fn run_simulation(args: &Args) {
+    match simulate_survey(&args.simulation) {
+        Ok(survey) => {
+            write_simulated_survey(&survey);
+            println!("Simulated {} responses", survey.rows.len());
         }
         Err(err) => {
-            println!("Failed to read CSV: {}", err);
+            println!("Failed to simulate responses: {}", err);
             process::exit(1);
         }
     }
 }
+
+/// Names the practice questions left out of the correlations or diffs, so their absence
+/// from the results is not mistaken for a finding.
+fn print_skipped_questions(skipped_questions: &[SkippedQuestionData]) {
+    for skipped in skipped_questions {
+        println!(
+            "Skipped {}: {}",
+            skipped.question,
+            skipped.reason.get_description()
+        );
+    }
+}
@@ -0,0 +1,14 @@
+use crate::{fns::factor::calculate_factor_analysis, tests::load_fixture};
+
+/// With fewer than three respondents no question has enough answers to correlate.
+#[test]
+fn skips_factoring_without_enough_answers() {
+    let mut feedbacks = load_fixture("golden_sparse");
+    feedbacks.truncate(2);
+
+    let analysis = calculate_factor_analysis(&feedbacks);
+    assert_eq!(analysis.respondent_count, 2);
+    assert!(analysis.questions.is_empty());
+    assert!(analysis.scree.is_empty());
+    assert!(analysis.solutions.is_empty());
+}
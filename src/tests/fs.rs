@@ -0,0 +1,40 @@
+use crate::{
+    fns::{
+        frequency::calculate_frequencies, fs::get_default_columns,
+        rank::calculate_strategy_rankings,
+    },
+    tests::load_fixture,
+    util::types::{MissingDataStrategy, QuestionFrequencyData, SkippedQuestionData},
+};
+use serde::Serialize;
+
+/// The columns of a row in its table, without its child tables.
+fn get_row_columns<T: Serialize>(row: &T) -> Vec<String> {
+    serde_json::to_value(row)
+        .unwrap()
+        .as_object()
+        .unwrap()
+        .iter()
+        .filter(|(_, field)| !field.is_array())
+        .map(|(key, _)| key.to_owned())
+        .collect()
+}
+
+/// An empty table gets the same header as a table with rows, child tables included.
+#[test]
+fn writes_the_header_of_empty_tables() {
+    let feedbacks = load_fixture("golden_sparse");
+
+    let (_, skipped_questions) =
+        calculate_strategy_rankings(&feedbacks, &MissingDataStrategy::Pairwise).unwrap();
+    assert_eq!(
+        get_default_columns::<SkippedQuestionData>(),
+        get_row_columns(&skipped_questions[0])
+    );
+
+    let frequencies = calculate_frequencies(&feedbacks);
+    assert_eq!(
+        get_default_columns::<QuestionFrequencyData>(),
+        get_row_columns(&frequencies[0])
+    );
+}
@@ -0,0 +1,145 @@
+//! Checks the correlations, impacts and diffs against reference values of the fixture datasets
+//! in `tests/fixtures`. The expected values are written by `tests/fixtures/reference.R` with
+//! `cor.test(x, y, method = "spearman", exact = FALSE)`; rerun it after changing a fixture.
+
+use crate::{
+    fns::{
+        diff::calculate_diffs,
+        impact::get_impact_averages,
+        question::get_questions_of_kind,
+        rank::{calculate_rankings, calculate_strategy_rankings},
+    },
+    tests::{get_fixture_path, load_fixture},
+    util::types::{
+        MissingDataStrategy, QuestionCorrelationData, QuestionDataDiffs, QuestionImpactData,
+        QuestionKind, SkipReason,
+    },
+};
+use serde_json::Value;
+use std::fs;
+
+/// The results are rounded to five decimal places.
+static TOLERANCE: f64 = 1e-4;
+
+fn read_expected(name: &str) -> Value {
+    serde_json::from_str(
+        &fs::read_to_string(get_fixture_path(&format!("{}.expected.json", name))).unwrap(),
+    )
+    .unwrap()
+}
+
+fn assert_close(actual: f64, expected: &Value, context: &str) {
+    let expected = expected.as_f64().unwrap();
+    assert!(
+        (actual - expected).abs() < TOLERANCE,
+        "{}: expected {}, got {}",
+        context,
+        expected,
+        actual
+    );
+}
+
+fn assert_correlations(correlations: &[QuestionCorrelationData], expected: &Value) {
+    let expected = expected["correlations"].as_object().unwrap();
+    assert_eq!(correlations.len(), expected.len());
+
+    for correlation in correlations {
+        let question = correlation.question.as_str();
+        let reference = &expected[question];
+        assert_close(correlation.rho, &reference["rho"], question);
+        // The crate reports the one-sided p-value; R's default is two-sided.
+        assert_close(correlation.p_value * 2f64, &reference["p_value"], question);
+        assert_eq!(
+            correlation.answer_count as u64,
+            reference["answer_count"].as_u64().unwrap(),
+            "{}",
+            question
+        );
+    }
+}
+
+fn assert_impacts(impacts: &[QuestionImpactData], expected: &Value) {
+    let expected = expected["impacts"].as_object().unwrap();
+    assert_eq!(impacts.len(), expected.len());
+
+    for impact in impacts {
+        let question = impact.question.as_str();
+        let reference = &expected[question];
+        assert_close(
+            impact.impact_average as f64,
+            &reference["impact_average"],
+            question,
+        );
+        assert_eq!(
+            impact.answer_count as u64,
+            reference["answer_count"].as_u64().unwrap(),
+            "{}",
+            question
+        );
+    }
+}
+
+fn assert_diffs(diffs: &[QuestionDataDiffs], expected: &Value) {
+    let expected = expected["diffs"].as_object().unwrap();
+    assert_eq!(diffs.len(), expected.len());
+
+    for diff in diffs {
+        let question = diff.question_correlation.as_str();
+        let reference = &expected[question];
+        assert_close(diff.diff, &reference["diff"], question);
+        assert_eq!(
+            serde_json::to_value(diff.quadrant).unwrap(),
+            reference["quadrant"],
+            "{}",
+            question
+        );
+    }
+}
+
+fn assert_fixture(name: &str) {
+    let feedbacks = load_fixture(name);
+    let expected = read_expected(name);
+
+    let correlations = calculate_rankings(&feedbacks);
+    let impacts = get_impact_averages(&feedbacks);
+    let diffs = calculate_diffs(&correlations, &impacts);
+
+    assert_correlations(&correlations, &expected);
+    assert_impacts(&impacts, &expected);
+    assert_diffs(&diffs, &expected);
+}
+
+#[test]
+fn matches_reference_values_with_ties() {
+    assert_fixture("golden_ties");
+}
+
+/// Questions with fewer than three applicable answers, or without variation, have no
+/// correlation and no diff.
+#[test]
+fn matches_reference_values_with_sparse_answers() {
+    assert_fixture("golden_sparse");
+}
+
+/// Every practice question of the survey has either a correlation or a reason why not.
+#[test]
+fn reports_questions_without_correlations() {
+    let feedbacks = load_fixture("golden_sparse");
+    let (correlations, skipped_questions) =
+        calculate_strategy_rankings(&feedbacks, &MissingDataStrategy::Pairwise).unwrap();
+
+    assert!(!skipped_questions.is_empty());
+    assert_eq!(
+        correlations.len() + skipped_questions.len(),
+        get_questions_of_kind(QuestionKind::Practice).count()
+    );
+    for skipped in skipped_questions.iter() {
+        assert!(!correlations.iter().any(|c| c.question == skipped.question));
+        assert_eq!(
+            skipped.reason == SkipReason::TooFewAnswers,
+            skipped.answer_count < 3,
+            "{}",
+            skipped.question
+        );
+    }
+}
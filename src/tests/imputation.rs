@@ -0,0 +1,142 @@
+use crate::{
+    fns::{
+        question::get_practice,
+        rank::{calculate_rankings, calculate_strategy_rankings},
+        score::get_score_columns,
+        stats::get_pooled_correlation,
+    },
+    tests::load_fixture,
+    util::{
+        types::{MissingDataStrategy, QuestionCorrelationData},
+        vars,
+    },
+};
+
+/// Imputations that disagree widen the pooled interval around the same correlation.
+#[test]
+fn pools_imputation_variance_into_the_interval() {
+    let (rho, lower, upper, p_value) = get_pooled_correlation(&[0.3; 5], 50).unwrap();
+    let (spread_rho, spread_lower, spread_upper, spread_p_value) =
+        get_pooled_correlation(&[0.1, 0.2, 0.3, 0.4, 0.5], 50).unwrap();
+
+    assert!((rho - 0.3).abs() < 1e-12);
+    assert!(lower < rho && rho < upper);
+    assert!((spread_rho - 0.3).abs() < 0.01);
+    assert!(spread_lower < lower && spread_upper > upper);
+    assert!(spread_p_value > p_value);
+    assert_eq!(get_pooled_correlation(&[0.3; 5], 3), None);
+    assert_eq!(get_pooled_correlation(&[0.3, f64::NAN], 50), None);
+}
+
+/// Imputation leaves the rho of a question without unscored answers as it is, and every
+/// pooled correlation uses every respondent.
+#[test]
+fn keeps_complete_questions_and_uses_every_respondent() {
+    let feedbacks = load_fixture("golden_ties");
+    let strategy = MissingDataStrategy::MultipleImputation {
+        imputation_count: 3,
+        iteration_count: 2,
+    };
+    let (pooled, _) = calculate_strategy_rankings(&feedbacks, &strategy).unwrap();
+    let pairwise = calculate_rankings(&feedbacks);
+
+    let rhos = |correlations: &[QuestionCorrelationData]| -> Vec<f64> {
+        correlations.iter().map(|c| c.rho).collect()
+    };
+    assert_eq!(
+        rhos(&pooled),
+        rhos(
+            &calculate_strategy_rankings(&feedbacks, &strategy)
+                .unwrap()
+                .0
+        )
+    );
+    assert!(
+        pooled
+            .iter()
+            .all(|c| c.imputed_answer_count == Some(feedbacks.len()))
+    );
+
+    // The observed answers are reported apart from the imputed ones
+    for correlation in pairwise.iter() {
+        let imputed = pooled
+            .iter()
+            .find(|c| c.question == correlation.question)
+            .unwrap();
+        assert_eq!(imputed.answer_count, correlation.answer_count);
+        assert_eq!(correlation.imputed_answer_count, None);
+    }
+
+    let complete = pairwise
+        .iter()
+        .filter(|c| c.answer_count == feedbacks.len());
+    for correlation in complete {
+        let imputed = pooled
+            .iter()
+            .find(|c| c.question == correlation.question)
+            .unwrap();
+        assert_eq!(imputed.rho, correlation.rho, "{}", correlation.question);
+    }
+}
+
+#[test]
+fn restricts_listwise_questions_to_complete_respondents() {
+    let feedbacks = load_fixture("golden_ties");
+    let columns = get_score_columns(&feedbacks);
+    let keys: Vec<String> = columns.questions[..2]
+        .iter()
+        .map(|c| c.question.to_owned())
+        .collect();
+    let complete_count = (0..columns.get_respondent_count())
+        .filter(|i| {
+            columns.questions[..2]
+                .iter()
+                .all(|c| c.scores[*i] != vars::WEIGHTING_NOT_APPLICABLE)
+        })
+        .count();
+
+    let (correlations, _) = calculate_strategy_rankings(
+        &feedbacks,
+        &MissingDataStrategy::Listwise {
+            questions: vec![
+                get_practice(&keys[0]).unwrap().id.to_owned(),
+                keys[1].clone(),
+            ],
+        },
+    )
+    .unwrap();
+
+    for key in keys.iter() {
+        if let Some(correlation) = correlations.iter().find(|c| c.question == *key) {
+            assert_eq!(correlation.answer_count, complete_count, "{}", key);
+        }
+    }
+    assert!(
+        correlations
+            .iter()
+            .all(|c| c.answer_count <= complete_count)
+    );
+
+    let unknown = MissingDataStrategy::Listwise {
+        questions: vec!["P00".to_owned()],
+    };
+    assert!(calculate_strategy_rankings(&feedbacks, &unknown).is_err());
+}
+
+/// The run parameters of `results.json` name the strategy with its parameters.
+#[test]
+fn records_the_strategy_in_the_run_parameters() {
+    let listwise = MissingDataStrategy::Listwise {
+        questions: vec!["P01".to_owned()],
+    };
+    let value = serde_json::to_value(&listwise).unwrap();
+    assert_eq!(value["strategy"], "listwise");
+    assert_eq!(value["questions"], serde_json::json!(["P01"]));
+
+    let mice = MissingDataStrategy::MultipleImputation {
+        imputation_count: 5,
+        iteration_count: vars::IMPUTATION_ITERATIONS,
+    };
+    let value = serde_json::to_value(&mice).unwrap();
+    assert_eq!(value["imputation_count"], 5);
+}
@@ -0,0 +1,58 @@
+use crate::{
+    fns::{influence::calculate_influences, rank::calculate_rankings, util::assign_ids},
+    tests::load_fixture,
+    util::types::Feedback,
+};
+
+fn read_fixture(name: &str) -> Vec<Feedback> {
+    let mut feedbacks = load_fixture(name);
+    assign_ids(&mut feedbacks);
+    feedbacks
+}
+
+/// Leaving out the most influential respondent gives the same rho as rerunning the
+/// correlations without them.
+#[test]
+fn matches_correlations_without_the_respondent() {
+    let feedbacks = read_fixture("golden_ties");
+
+    for influence in calculate_influences(&feedbacks) {
+        let respondent = &influence.influential_respondents[0];
+        let remaining: Vec<Feedback> = feedbacks
+            .iter()
+            .filter(|f| f.id != respondent.respondent_id)
+            .cloned()
+            .collect();
+        let correlation = calculate_rankings(&remaining)
+            .into_iter()
+            .find(|c| c.question == influence.question)
+            .unwrap();
+
+        assert_eq!(respondent.rho, correlation.rho, "{}", influence.question);
+        assert_eq!(
+            respondent.p_value, correlation.p_value,
+            "{}",
+            influence.question
+        );
+        assert!(influence.rho_min <= respondent.rho && respondent.rho <= influence.rho_max);
+        assert!(
+            influence
+                .influential_respondents
+                .windows(2)
+                .all(|pair| pair[0].rho_change.abs() >= pair[1].rho_change.abs())
+        );
+    }
+}
+
+#[test]
+fn flags_correlations_that_one_respondent_makes_significant() {
+    let influences = calculate_influences(&read_fixture("golden_ties"));
+
+    for influence in influences.iter() {
+        assert_eq!(
+            influence.is_significance_changed,
+            influence.significance_changing_count > 0
+        );
+    }
+    assert!(influences.iter().any(|i| i.is_significance_changed));
+}
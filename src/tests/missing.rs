@@ -0,0 +1,51 @@
+use crate::{
+    fns::{
+        missing::calculate_missing_data,
+        util::{get_answer_score, get_missing_reason},
+    },
+    tests::load_fixture,
+    util::{types::MissingReason, vars},
+};
+
+#[test]
+fn classifies_unscored_answers_by_reason() {
+    let answers = [
+        ("I don't know", Some(MissingReason::DontKnow)),
+        ("Not applicable", Some(MissingReason::NotApplicable)),
+        ("Regex patterns were not used", Some(MissingReason::NotUsed)),
+        ("Yes, sometimes", None),
+    ];
+
+    for (answer, reason) in answers {
+        assert_eq!(get_missing_reason(answer), reason);
+        assert_eq!(
+            get_answer_score(answer) == vars::WEIGHTING_NOT_APPLICABLE,
+            reason.is_some()
+        );
+    }
+}
+
+#[test]
+fn counts_every_answer_once() {
+    let feedbacks = load_fixture("golden_sparse");
+
+    for missing in calculate_missing_data(&feedbacks, false) {
+        assert_eq!(
+            missing.answer_count
+                + missing.dont_know_count
+                + missing.not_applicable_count
+                + missing.not_used_count,
+            missing.respondent_count
+        );
+        assert!(missing.dont_know_quality_test.is_none());
+    }
+
+    let tested = calculate_missing_data(&feedbacks, true);
+    assert!(tested.iter().any(|m| m.dont_know_quality_test.is_some()));
+    // Without variation in the quality estimates there is no test either
+    for missing in tested {
+        if missing.dont_know_quality_test.is_some() {
+            assert!(missing.dont_know_count > 0 && missing.answer_count > 0);
+        }
+    }
+}
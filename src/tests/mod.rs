@@ -0,0 +1,40 @@
+mod factor;
+mod fs;
+mod golden;
+mod imputation;
+mod influence;
+mod missing;
+mod ordinal;
+mod power;
+mod question;
+mod ranking;
+mod scaling;
+mod score;
+mod simulate;
+mod weighting;
+
+use crate::{
+    fns::csv::read_csv,
+    util::types::{Feedback, InputFile},
+};
+
+/// Path of a file in `tests/fixtures`.
+fn get_fixture_path(file_name: &str) -> String {
+    format!(
+        "{}/tests/fixtures/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        file_name
+    )
+}
+
+/// The feedbacks of the fixture dataset `name`, read as a wave of the same name.
+fn load_fixture(name: &str) -> Vec<Feedback> {
+    read_csv(
+        &[InputFile {
+            wave: name.to_owned(),
+            path: get_fixture_path(&format!("{}.csv", name)),
+        }],
+        &[],
+    )
+    .unwrap()
+}
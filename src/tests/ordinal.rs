@@ -0,0 +1,63 @@
+use crate::fns::ordinal::fit_ordinal_regression;
+
+static TOLERANCE: f64 = 1e-4;
+
+fn assert_close(actual: f64, expected: f64, context: &str) {
+    assert!(
+        (actual - expected).abs() < TOLERANCE,
+        "{}: expected {}, got {}",
+        context,
+        expected,
+        actual
+    );
+}
+
+/// With two categories and a binary predictor the model is a logistic regression on a 2x2
+/// table: the odds ratio is the cross-product ratio with Woolf's standard error.
+#[test]
+fn fits_two_by_two_table() {
+    let predictors = [0f64, 0f64, 0f64, 0f64, 1f64, 1f64, 1f64, 1f64];
+    let outcomes = [1, 1, 1, 2, 1, 2, 2, 2];
+    let result = fit_ordinal_regression("table", &predictors, &outcomes).unwrap();
+
+    assert!(result.converged);
+    assert_close(result.odds_ratio, 9f64, "odds ratio");
+    assert_close(result.coefficient, 9f64.ln(), "coefficient");
+    assert_close(
+        result.standard_error,
+        f64::sqrt(1f64 / 3f64 + 1f64 + 1f64 + 1f64 / 3f64),
+        "standard error",
+    );
+    assert_close(result.thresholds[0].estimate, 3f64.ln(), "threshold");
+    assert_eq!(result.proportional_odds_chi_squared, None);
+}
+
+/// Cumulative proportions of 0.4 and 0.8 without and 0.1 and 0.4 with the predictor shift
+/// both cumulative logits by ln 6, so the fit reproduces them and proportional odds hold.
+#[test]
+fn reproduces_proportional_cumulative_logits() {
+    let mut predictors: Vec<f64> = Vec::new();
+    let mut outcomes: Vec<i8> = Vec::new();
+    for (predictor, counts) in [(0f64, [4, 4, 2]), (1f64, [1, 3, 6])] {
+        for (category, count) in counts.iter().enumerate() {
+            predictors.extend(std::iter::repeat_n(predictor, *count));
+            outcomes.extend(std::iter::repeat_n(category as i8 + 1, *count));
+        }
+    }
+    let result = fit_ordinal_regression("table", &predictors, &outcomes).unwrap();
+
+    assert!(result.converged);
+    assert_close(result.coefficient, 6f64.ln(), "coefficient");
+    assert_close(
+        result.thresholds[0].estimate,
+        (2f64 / 3f64).ln(),
+        "threshold 1|2",
+    );
+    assert_close(result.thresholds[1].estimate, 4f64.ln(), "threshold 2|3");
+    assert_eq!(result.proportional_odds_df, 1);
+    assert_close(
+        result.proportional_odds_chi_squared.unwrap(),
+        0f64,
+        "Brant statistic",
+    );
+}
@@ -0,0 +1,73 @@
+use crate::{
+    fns::{
+        power::{calculate_power_analysis, get_simulated_power, get_simulated_sample_size},
+        stats::{
+            PEARSON_FISHER_VARIANCE_FACTOR, SPEARMAN_FISHER_VARIANCE_FACTOR, get_correlation_power,
+            get_required_sample_size,
+        },
+    },
+    tests::load_fixture,
+};
+
+#[test]
+fn required_sample_size_is_the_smallest_reaching_the_power() {
+    for factor in [
+        PEARSON_FISHER_VARIANCE_FACTOR,
+        SPEARMAN_FISHER_VARIANCE_FACTOR,
+    ] {
+        for rho in [0.1, 0.3, -0.45, 0.7] {
+            let n = get_required_sample_size(rho, 0.8, factor).unwrap();
+            assert!(get_correlation_power(rho, n, factor) >= 0.8);
+            assert!(get_correlation_power(rho, n - 1, factor) < 0.8);
+        }
+    }
+
+    assert_eq!(
+        get_required_sample_size(0.3, 0.8, PEARSON_FISHER_VARIANCE_FACTOR),
+        Some(68)
+    );
+    assert_eq!(
+        get_required_sample_size(0f64, 0.8, PEARSON_FISHER_VARIANCE_FACTOR),
+        None
+    );
+}
+
+#[test]
+fn reports_power_curves_per_question() {
+    let feedbacks = load_fixture("golden_ties");
+    let analysis = calculate_power_analysis(&feedbacks, &[0.3], 0.8, 50);
+
+    assert_eq!(
+        analysis.effect_sizes[0].required_answer_count_spearman,
+        Some(72)
+    );
+    assert!(!analysis.questions.is_empty());
+
+    for question in analysis.questions.iter() {
+        for pair in question.power_curve.windows(2) {
+            assert!(pair[0].power_spearman <= pair[1].power_spearman);
+            assert!(pair[0].power_spearman <= pair[0].power_pearson);
+        }
+        for point in question.power_curve.iter() {
+            assert!((0f64..=1f64).contains(&point.power_simulated));
+        }
+    }
+}
+
+/// The simulated sample size is any count between the grid points of the power curve.
+#[test]
+fn bisects_the_simulated_sample_size() {
+    let scores: Vec<f64> = (0..40).map(|i| (i % 4) as f64).collect();
+    let qualities: Vec<f64> = (0..40).map(|i| ((i % 4) + (i % 3)) as f64).collect();
+
+    let n = get_simulated_sample_size(&scores, &qualities, 1f64, 0.8, 100).unwrap();
+    assert!(get_simulated_power(&scores, &qualities, 1f64, n, 100) >= 0.8);
+    assert!(get_simulated_power(&scores, &qualities, 1f64, n - 1, 100) < 0.8);
+
+    // Without a correlation no sample size reaches the power
+    let uncorrelated: Vec<f64> = (0..40).map(|i| (i / 4 % 2) as f64).collect();
+    assert_eq!(
+        get_simulated_sample_size(&scores, &uncorrelated, 1f64, 0.8, 20),
+        None
+    );
+}
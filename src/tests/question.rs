@@ -0,0 +1,69 @@
+use crate::{
+    fns::{
+        question::{
+            get_paired_field_name, get_questions, get_questions_of_kind, validate_registry,
+        },
+        util::{get_answer_score, get_missing_reason},
+    },
+    util::{
+        types::{Question, QuestionKind},
+        vars,
+    },
+};
+
+#[test]
+fn pairs_every_practice_with_an_impact_rating() {
+    assert_eq!(get_questions_of_kind(QuestionKind::Practice).count(), 32);
+    assert_eq!(get_questions_of_kind(QuestionKind::Impact).count(), 32);
+    assert_eq!(get_questions_of_kind(QuestionKind::Outcome).count(), 1);
+
+    for practice in get_questions_of_kind(QuestionKind::Practice) {
+        let impact = get_paired_field_name(practice.field_name).unwrap();
+        assert_eq!(get_paired_field_name(impact), Some(practice.field_name));
+    }
+}
+
+/// The answers of a practice are scored from the lowest to the highest, and its unscored
+/// answers have a reason to be missing.
+#[test]
+fn orders_the_answers_of_every_practice() {
+    for practice in get_questions_of_kind(QuestionKind::Practice) {
+        let scores: Vec<i8> = practice
+            .answers
+            .iter()
+            .map(|a| get_answer_score(a))
+            .collect();
+        assert_eq!(scores.len(), 3, "{}", practice.id);
+        assert!(scores.windows(2).all(|w| w[0] < w[1]), "{}", practice.id);
+        assert!(
+            !scores.contains(&vars::WEIGHTING_NOT_APPLICABLE),
+            "{}",
+            practice.id
+        );
+
+        assert!(!practice.unscored_answers.is_empty());
+        for answer in practice.unscored_answers {
+            assert!(get_missing_reason(answer).is_some(), "{}", practice.id);
+        }
+    }
+}
+
+#[test]
+fn rejects_broken_pairings() {
+    let mut registry: Vec<Question> = get_questions().to_vec();
+    let impact = registry
+        .iter_mut()
+        .find(|q| q.kind == QuestionKind::Impact)
+        .unwrap();
+    impact.pair = Some("was_initial_project_plan_written");
+
+    assert!(validate_registry(&registry).is_err());
+}
+
+#[test]
+fn rejects_duplicate_ids() {
+    let mut registry: Vec<Question> = get_questions().to_vec();
+    registry.push(registry[0].clone());
+
+    assert!(validate_registry(&registry).is_err());
+}
@@ -0,0 +1,91 @@
+use crate::{
+    fns::{
+        rank::{get_pearson_correlation, get_spearman_correlation},
+        ranking::{get_ranking, get_tie_sum},
+    },
+    util::types::{Ranking, TieMethod},
+};
+
+static VALUES: [f64; 6] = [2.0, 3.0, 2.0, 1.0, 3.0, 2.0];
+
+fn get_ranks(method: TieMethod) -> Vec<f64> {
+    get_ranking(&VALUES, method).ranks
+}
+
+#[test]
+fn ranks_ties_by_average() {
+    assert_eq!(
+        get_ranks(TieMethod::Average),
+        vec![4.0, 1.5, 4.0, 6.0, 1.5, 4.0]
+    );
+}
+
+#[test]
+fn ranks_ties_by_min() {
+    assert_eq!(
+        get_ranks(TieMethod::Min),
+        vec![3.0, 1.0, 3.0, 6.0, 1.0, 3.0]
+    );
+}
+
+#[test]
+fn ranks_ties_by_max() {
+    assert_eq!(
+        get_ranks(TieMethod::Max),
+        vec![5.0, 2.0, 5.0, 6.0, 2.0, 5.0]
+    );
+}
+
+#[test]
+fn ranks_ties_densely() {
+    assert_eq!(
+        get_ranks(TieMethod::Dense),
+        vec![2.0, 1.0, 2.0, 3.0, 1.0, 2.0]
+    );
+}
+
+#[test]
+fn ranks_ties_in_order_of_appearance() {
+    assert_eq!(
+        get_ranks(TieMethod::Ordinal),
+        vec![3.0, 1.0, 4.0, 6.0, 2.0, 5.0]
+    );
+}
+
+#[test]
+fn returns_tie_sizes_from_the_highest_value() {
+    let ranking = get_ranking(&VALUES, TieMethod::Average);
+    assert_eq!(ranking.tie_sizes, vec![2, 3, 1]);
+    assert_eq!(get_tie_sum(&ranking.tie_sizes), 6.0 + 24.0);
+}
+
+#[test]
+fn ranks_without_ties_or_values() {
+    assert_eq!(
+        get_ranking(&[0.5, -1.0, 2.0], TieMethod::Average),
+        Ranking {
+            ranks: vec![2.0, 3.0, 1.0],
+            tie_sizes: vec![1, 1, 1],
+        }
+    );
+    assert_eq!(
+        get_ranking(&[], TieMethod::Dense),
+        Ranking {
+            ranks: vec![],
+            tie_sizes: vec![],
+        }
+    );
+}
+
+/// The tie-corrected formula must agree with the Pearson correlation of fractional ranks.
+#[test]
+fn corrects_spearman_correlation_for_ties() {
+    let quality = [4.0, 5.0, 3.0, 1.0, 4.0, 2.0];
+    let (rho, _) = get_spearman_correlation(&VALUES, &quality);
+    let expected = get_pearson_correlation(
+        &get_ranks(TieMethod::Average),
+        &get_ranking(&quality, TieMethod::Average).ranks,
+    );
+
+    assert!((rho - expected).abs() < 1e-12, "{} != {}", rho, expected);
+}
@@ -0,0 +1,38 @@
+use crate::{
+    fns::{scaling::calculate_optimal_scalings, stats::get_isotonic_fit},
+    tests::load_fixture,
+    util::vars,
+};
+
+#[test]
+fn pools_adjacent_violators() {
+    assert_eq!(
+        get_isotonic_fit(&[1.0, 3.0, 2.0, 4.0], &[1.0, 1.0, 1.0, 1.0]),
+        vec![1.0, 2.5, 2.5, 4.0]
+    );
+    assert_eq!(
+        get_isotonic_fit(&[3.0, 1.0, 2.0, 1.0], &[1.0, 1.0, 2.0, 4.0]),
+        vec![1.5, 1.5, 1.5, 1.5]
+    );
+}
+
+/// The estimated values keep the answer order, span the weighting scale and associate at
+/// least as strongly with quality as the fixed weightings they were estimated from.
+#[test]
+fn estimates_monotone_category_values() {
+    let feedbacks = load_fixture("golden_ties");
+    let scalings = calculate_optimal_scalings(&feedbacks);
+    assert!(!scalings.is_empty());
+
+    for scaling in scalings.iter() {
+        let values: Vec<f64> = scaling.categories.iter().filter_map(|c| c.value).collect();
+        if values.is_empty() {
+            continue;
+        }
+
+        assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(values[0], vars::WEIGHTING_FULL_NEG as f64);
+        assert_eq!(values[values.len() - 1], vars::WEIGHTING_FULL as f64);
+        assert!(scaling.optimal_r.unwrap().abs() >= scaling.fixed_r.abs() - 1e-9);
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{
+    fns::{question::get_questions_of_kind, ranking::get_ranking, score::get_score_columns},
+    tests::load_fixture,
+    util::{
+        types::{QuestionKind, TieMethod},
+        vars,
+    },
+};
+
+#[test]
+fn scores_every_question_in_columns() {
+    let feedbacks = load_fixture("golden_sparse");
+    let columns = get_score_columns(&feedbacks);
+
+    assert_eq!(columns.get_respondent_count(), feedbacks.len());
+    assert!(
+        columns
+            .questions
+            .iter()
+            .map(|c| c.question)
+            .eq(get_questions_of_kind(QuestionKind::Practice).map(|q| q.field_name))
+    );
+
+    for column in columns.questions.iter() {
+        assert_eq!(column.scores.len(), feedbacks.len());
+        for (score, rank) in column.scores.iter().zip(column.ranks.iter()) {
+            assert_eq!(
+                rank.is_none(),
+                *score == vars::WEIGHTING_NOT_APPLICABLE,
+                "{}",
+                column.question
+            );
+        }
+
+        let (scores, qualities) = columns.get_applicable_scores(column);
+        assert_eq!(
+            column.get_applicable_ranking(),
+            get_ranking(&scores, TieMethod::Average)
+        );
+        assert_eq!(scores.len(), qualities.len());
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{
+    fns::{
+        csv::read_csv, fs::write_survey_csv, question::get_questions_of_kind,
+        rank::calculate_rankings, simulate::simulate_survey,
+    },
+    util::types::{InputFile, QuestionKind, SimulationConfig},
+};
+use std::{env, fs};
+
+fn get_config(seed: u64) -> SimulationConfig {
+    SimulationConfig {
+        respondent_count: 500,
+        seed,
+        not_applicable_rate: 0.1,
+        answer_weights: [0.3, 0.3, 0.4],
+        quality_weights: [0.05, 0.15, 0.3, 0.35, 0.15],
+        correlations: vec![
+            ("P01".to_owned(), 0.6),
+            ("were_draft_design_documents_written".to_owned(), -0.4),
+        ],
+    }
+}
+
+#[test]
+fn reproduces_responses_from_the_seed() {
+    let survey = simulate_survey(&get_config(7)).unwrap();
+
+    assert_eq!(survey.rows, simulate_survey(&get_config(7)).unwrap().rows);
+    assert_ne!(survey.rows, simulate_survey(&get_config(8)).unwrap().rows);
+}
+
+/// Every practice gets its own answers, including the partially negative and the unscored
+/// ones, in the column of its question text.
+#[test]
+fn uses_the_answers_of_each_practice() {
+    let survey = simulate_survey(&get_config(7)).unwrap();
+
+    for question in get_questions_of_kind(QuestionKind::Practice) {
+        let column = survey
+            .headers
+            .iter()
+            .position(|header| header.trim_end() == question.text)
+            .unwrap();
+
+        for answer in question.answers.iter().chain(question.unscored_answers) {
+            assert!(
+                survey.rows.iter().any(|row| row[column] == *answer),
+                "{}: {}",
+                question.id,
+                answer
+            );
+        }
+    }
+}
+
+/// The generated export reads back, and only the chosen practices correlate with quality.
+#[test]
+fn round_trips_through_the_csv_reader() {
+    let path = env::temp_dir().join(format!("simulated_feedbacks_{}.csv", std::process::id()));
+    let path = path.to_string_lossy().into_owned();
+    write_survey_csv(&simulate_survey(&get_config(7)).unwrap(), &path).unwrap();
+
+    let feedbacks = read_csv(
+        &[InputFile {
+            wave: "simulated".to_owned(),
+            path: path.to_owned(),
+        }],
+        &[],
+    )
+    .unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(feedbacks.len(), 500);
+
+    for correlation in calculate_rankings(&feedbacks) {
+        match correlation.question.as_str() {
+            "were_requirements_documents_written" => assert!(correlation.rho > 0.45),
+            "were_draft_design_documents_written" => assert!(correlation.rho < -0.3),
+            _ => assert!(correlation.rho.abs() < 0.2, "{}", correlation.question),
+        }
+    }
+}
+
+#[test]
+fn rejects_invalid_configurations() {
+    let mut config = get_config(7);
+    config.correlations.push(("P99".to_owned(), 0.3));
+    assert!(simulate_survey(&config).is_err());
+
+    let mut config = get_config(7);
+    config
+        .correlations
+        .push(("were_requirements_documents_written".to_owned(), 0.3));
+    assert!(simulate_survey(&config).is_err());
+
+    let mut config = get_config(7);
+    config.quality_weights = [0f64; 5];
+    assert!(simulate_survey(&config).is_err());
+}
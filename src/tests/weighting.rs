@@ -0,0 +1,52 @@
+use crate::{
+    fns::{
+        rank::calculate_rankings,
+        weighting::{WEIGHTING_SCHEMES, calculate_weighting_sensitivity},
+    },
+    tests::load_fixture,
+    util::vars,
+};
+
+#[test]
+fn keeps_not_applicable_answers() {
+    for scheme in WEIGHTING_SCHEMES.iter() {
+        assert_eq!(
+            scheme.get_score(vars::WEIGHTING_NOT_APPLICABLE),
+            vars::WEIGHTING_NOT_APPLICABLE
+        );
+    }
+}
+
+/// The standard scheme reproduces the correlations, and an order-preserving scheme leaves
+/// Spearman's rho unchanged but moves Pearson's r.
+#[test]
+fn compares_schemes_with_the_standard_weightings() {
+    let feedbacks = load_fixture("golden_ties");
+    let rankings = calculate_rankings(&feedbacks);
+    let sensitivity = calculate_weighting_sensitivity(&feedbacks);
+
+    assert_eq!(sensitivity.questions.len(), rankings.len());
+    assert_eq!(sensitivity.schemes[2].scheme, "equal_interval");
+    assert_eq!(sensitivity.schemes[2].max_absolute_rho_change, 0f64);
+    assert!(sensitivity.schemes[2].max_absolute_r_change > 0f64);
+
+    for question in sensitivity.questions.iter() {
+        let ranking = rankings
+            .iter()
+            .find(|r| r.question == question.question)
+            .unwrap();
+        assert_eq!(question.rho, ranking.rho);
+        assert_eq!(question.schemes[0].rho_change, Some(0f64));
+        assert_eq!(question.schemes[0].r_change, Some(0f64));
+        assert!(question.r_min <= question.r && question.r <= question.r_max);
+        assert!(question.rho_min <= question.rho && question.rho <= question.rho_max);
+
+        // Excluding partial answers never adds applicable answers
+        let excluded = &question.schemes[4];
+        assert!(
+            excluded
+                .answer_count
+                .is_none_or(|n| n <= ranking.answer_count)
+        );
+    }
+}
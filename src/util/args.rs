@@ -0,0 +1,547 @@
+use crate::util::{
+    types::{InputFile, MissingDataStrategy, SimulationConfig, TieMethod},
+    vars,
+};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use std::{collections::HashMap, path::Path};
+
+static DEFAULT_INPUT: &str = "./data/feedbacks.csv";
+
+pub enum Mode {
+    Analyse,
+    CompareWaves,
+    Report,
+    Export,
+    Compare,
+    Simulate,
+    Power,
+    Sensitivity,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Json,
+    Csv,
+    Tsv,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Tsv,
+    Parquet,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Html,
+    Markdown,
+    Latex,
+}
+
+pub struct Args {
+    pub mode: Mode,
+    pub inputs: Vec<InputFile>,
+    pub group_columns: Vec<String>,
+    pub output_format: OutputFormat,
+    pub export_format: ExportFormat,
+    /// How tied scores and qualities share ranks in the exported dataset.
+    pub tie_method: TieMethod,
+    /// Whether the analysis estimates category values of the answers from the data.
+    pub optimal_scaling: bool,
+    /// Whether the analysis tests if "don't know" respondents differ in quality.
+    pub test_dont_know: bool,
+    /// Which respondents the correlations of the analysis and reports use.
+    pub missing_data: MissingDataStrategy,
+    pub report_formats: Vec<ReportFormat>,
+    /// Decimal places of the numbers in reports.
+    pub precision: usize,
+    /// LaTeX table captions keyed by table name.
+    pub table_captions: HashMap<String, String>,
+    /// LaTeX table labels keyed by table name.
+    pub table_labels: HashMap<String, String>,
+    /// Result directories or `results.json` files to compare, earlier run first.
+    pub compare_paths: Vec<String>,
+    /// Largest accepted absolute change in rho, p-value or impact average when comparing.
+    pub tolerance: Option<f64>,
+    pub simulation: SimulationConfig,
+    /// Correlations the power analysis reports the required sample sizes for.
+    pub effect_sizes: Vec<f64>,
+    pub target_power: f64,
+    /// Resamples per question and sample size for the simulated power.
+    pub simulation_count: usize,
+}
+
+pub fn get_args() -> Args {
+    let matches = Command::new(env!("CARGO_PKG_NAME"))
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Analyses development practice survey results")
+        .arg(
+            Arg::new("input")
+                .long("input")
+                .short('i')
+                .value_name("[WAVE=]FILE")
+                .action(ArgAction::Append)
+                .global(true)
+                .help("Survey responses exported as CSV, optionally labelled with a wave; may be repeated"),
+        )
+        .arg(
+            Arg::new("group_by")
+                .long("group-by")
+                .short('g')
+                .value_name("COLUMN")
+                .action(ArgAction::Append)
+                .global(true)
+                .help("CSV column header to run subgroup analyses by; may be repeated"),
+        )
+        .arg(
+            Arg::new("output_format")
+                .long("output-format")
+                .short('o')
+                .value_parser(["json", "csv", "tsv"])
+                .default_value("json")
+                .global(true)
+                .help("Format of the result files; csv and tsv split nested results into one file per table"),
+        )
+        .arg(
+            Arg::new("optimal_scaling")
+                .long("optimal-scaling")
+                .action(ArgAction::SetTrue)
+                .help("Also estimate monotone category values of the answers from the data and cross-validate them"),
+        )
+        .arg(
+            Arg::new("test_dont_know")
+                .long("test-dont-know")
+                .action(ArgAction::SetTrue)
+                .help("Also test per question whether respondents answering \"I don't know\" differ in quality from those who answered"),
+        )
+        .arg(
+            Arg::new("missing_data")
+                .long("missing-data")
+                .value_parser(["pairwise", "listwise", "mice"])
+                .default_value("pairwise")
+                .global(true)
+                .help("Respondents of the correlations: those who answered each question, those who answered every --listwise-question, or all with unscored answers imputed by chained equations"),
+        )
+        .arg(
+            Arg::new("listwise_question")
+                .long("listwise-question")
+                .value_name("QUESTION")
+                .action(ArgAction::Append)
+                .global(true)
+                .help("Practice, by ID or field name, that respondents must have answered with --missing-data listwise; may be repeated"),
+        )
+        .arg(
+            Arg::new("imputations")
+                .long("imputations")
+                .value_name("COUNT")
+                .value_parser(clap::value_parser!(u64).range(2..))
+                .global(true)
+                .help("Imputed datasets that rho is pooled over with --missing-data mice [default: 20]"),
+        )
+        .subcommand(
+            Command::new("waves").about("Compare each survey wave with the one before it"),
+        )
+        .subcommand(
+            Command::new("report")
+                .about("Write reports of the correlation, impact and diff results")
+                .arg(
+                    Arg::new("report_format")
+                        .long("format")
+                        .short('f')
+                        .value_parser(["html", "markdown", "latex"])
+                        .action(ArgAction::Append)
+                        .default_value("html")
+                        .help("Report format; html embeds the charts as inline SVG; may be repeated"),
+                )
+                .arg(
+                    Arg::new("precision")
+                        .long("precision")
+                        .short('p')
+                        .value_name("DECIMALS")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Decimal places of the numbers in reports"),
+                )
+                .arg(
+                    Arg::new("caption")
+                        .long("caption")
+                        .value_name("TABLE=TEXT")
+                        .action(ArgAction::Append)
+                        .help("LaTeX caption of the correlations, impacts or diffs table; may be repeated"),
+                )
+                .arg(
+                    Arg::new("label")
+                        .long("label")
+                        .value_name("TABLE=LABEL")
+                        .action(ArgAction::Append)
+                        .help("LaTeX label of the correlations, impacts or diffs table; may be repeated"),
+                ),
+        )
+        .subcommand(
+            Command::new("export")
+                .about("Export the scored answers in long format, one row per respondent and question")
+                .arg(
+                    Arg::new("export_format")
+                        .long("format")
+                        .short('f')
+                        .value_parser(["json", "csv", "tsv", "parquet"])
+                        .default_value("csv")
+                        .help("Format of the exported dataset"),
+                )
+                .arg(
+                    Arg::new("tie_method")
+                        .long("tie-method")
+                        .value_parser(["average", "min", "max", "dense", "ordinal"])
+                        .default_value("average")
+                        .help("How tied scores and qualities share ranks; average matches Spearman's rho"),
+                ),
+        )
+        .subcommand(
+            Command::new("compare")
+                .about("Compare the results of two runs question by question")
+                .arg(
+                    Arg::new("before")
+                        .value_name("BEFORE")
+                        .required(true)
+                        .help("Result directory or results.json of the earlier run"),
+                )
+                .arg(
+                    Arg::new("after")
+                        .value_name("AFTER")
+                        .required(true)
+                        .help("Result directory or results.json of the later run"),
+                )
+                .arg(
+                    Arg::new("tolerance")
+                        .long("tolerance")
+                        .short('t')
+                        .value_name("CHANGE")
+                        .value_parser(clap::value_parser!(f64))
+                        .help("Exit with status 2 when a change in rho, p-value or impact average exceeds this, or a significance or quadrant changed"),
+                ),
+        )
+        .subcommand(
+            Command::new("simulate")
+                .about("Write simulated survey responses to out/simulated_feedbacks.csv")
+                .arg(
+                    Arg::new("respondents")
+                        .long("respondents")
+                        .short('n')
+                        .value_name("COUNT")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("100")
+                        .help("Number of simulated respondents"),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .short('s')
+                        .value_parser(clap::value_parser!(u64))
+                        .help("Seed of the random number generator; the same seed gives the same responses [default: 42]"),
+                )
+                .arg(
+                    Arg::new("na_rate")
+                        .long("na-rate")
+                        .value_name("RATE")
+                        .value_parser(clap::value_parser!(f64))
+                        .default_value("0.1")
+                        .help("Share of practice answers that are not applicable or unknown, and of impact ratings left blank"),
+                )
+                .arg(
+                    Arg::new("answer_weights")
+                        .long("answer-weights")
+                        .value_name("LOW,MIDDLE,HIGH")
+                        .value_parser(parse_weights::<3>)
+                        .default_value("0.3,0.3,0.4")
+                        .help("Relative frequencies of the lowest, middle and highest scored answer of each practice"),
+                )
+                .arg(
+                    Arg::new("quality_weights")
+                        .long("quality-weights")
+                        .value_name("Q1,Q2,Q3,Q4,Q5")
+                        .value_parser(parse_weights::<5>)
+                        .default_value("0.05,0.15,0.3,0.35,0.15")
+                        .help("Relative frequencies of the quality estimates 1 to 5"),
+                )
+                .arg(
+                    Arg::new("correlate")
+                        .long("correlate")
+                        .short('c')
+                        .value_name("QUESTION=RHO")
+                        .value_parser(parse_correlation)
+                        .action(ArgAction::Append)
+                        .help("Target Spearman's rho of a practice, by ID or field name, with the quality estimate; may be repeated"),
+                ),
+        )
+        .subcommand(
+            Command::new("power")
+                .about("Estimate the sample sizes needed to detect correlations in the next survey wave")
+                .arg(
+                    Arg::new("rho")
+                        .long("rho")
+                        .short('r')
+                        .value_parser(parse_rho)
+                        .action(ArgAction::Append)
+                        .default_values(["0.1", "0.3", "0.5"])
+                        .help("Correlation to report the required sample size for; may be repeated"),
+                )
+                .arg(
+                    Arg::new("power")
+                        .long("power")
+                        .short('p')
+                        .value_parser(parse_probability)
+                        .default_value("0.8")
+                        .help("Probability of detecting a correlation that the sample sizes are chosen for"),
+                )
+                .arg(
+                    Arg::new("simulations")
+                        .long("simulations")
+                        .value_name("COUNT")
+                        .value_parser(clap::value_parser!(u64).range(1..))
+                        .default_value("500")
+                        .help("Resamples of the observed answers per question and sample size"),
+                ),
+        )
+        .subcommand(
+            Command::new("sensitivity")
+                .about("Rerun the correlations under alternative answer weighting schemes"),
+        )
+        .get_matches();
+
+    let mode = match matches.subcommand_name() {
+        Some("waves") => Mode::CompareWaves,
+        Some("report") => Mode::Report,
+        Some("export") => Mode::Export,
+        Some("compare") => Mode::Compare,
+        Some("simulate") => Mode::Simulate,
+        Some("power") => Mode::Power,
+        Some("sensitivity") => Mode::Sensitivity,
+        _ => Mode::Analyse,
+    };
+
+    let matches: &ArgMatches = matches
+        .subcommand()
+        .map(|(_, sub_matches)| sub_matches)
+        .unwrap_or(&matches);
+
+    let inputs: Vec<InputFile> = matches
+        .get_many::<String>("input")
+        .map(|values| values.map(|value| get_input_file(value)).collect())
+        .unwrap_or_else(|| vec![get_input_file(DEFAULT_INPUT)]);
+
+    let export_format = match matches
+        .try_get_one::<String>("export_format")
+        .ok()
+        .flatten()
+        .map(String::as_str)
+    {
+        Some("json") => ExportFormat::Json,
+        Some("tsv") => ExportFormat::Tsv,
+        Some("parquet") => ExportFormat::Parquet,
+        _ => ExportFormat::Csv,
+    };
+
+    let tie_method = match matches
+        .try_get_one::<String>("tie_method")
+        .ok()
+        .flatten()
+        .map(String::as_str)
+    {
+        Some("min") => TieMethod::Min,
+        Some("max") => TieMethod::Max,
+        Some("dense") => TieMethod::Dense,
+        Some("ordinal") => TieMethod::Ordinal,
+        _ => TieMethod::Average,
+    };
+
+    let report_formats: Vec<ReportFormat> = matches
+        .try_get_many::<String>("report_format")
+        .ok()
+        .flatten()
+        .map(|values| {
+            values
+                .map(|value| match value.as_str() {
+                    "markdown" => ReportFormat::Markdown,
+                    "latex" => ReportFormat::Latex,
+                    _ => ReportFormat::Html,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let missing_data = match get_value::<String>(matches, "missing_data").as_deref() {
+        Some("listwise") => MissingDataStrategy::Listwise {
+            questions: matches
+                .get_many::<String>("listwise_question")
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default(),
+        },
+        Some("mice") => MissingDataStrategy::MultipleImputation {
+            imputation_count: get_value::<u64>(matches, "imputations")
+                .map_or(vars::IMPUTATION_COUNT, |count| count as usize),
+            iteration_count: vars::IMPUTATION_ITERATIONS,
+        },
+        _ => MissingDataStrategy::Pairwise,
+    };
+
+    let output_format = match matches
+        .get_one::<String>("output_format")
+        .map(String::as_str)
+    {
+        Some("csv") => OutputFormat::Csv,
+        Some("tsv") => OutputFormat::Tsv,
+        _ => OutputFormat::Json,
+    };
+
+    Args {
+        mode,
+        inputs,
+        group_columns: matches
+            .get_many::<String>("group_by")
+            .map(|values| values.cloned().collect())
+            .unwrap_or_default(),
+        output_format,
+        export_format,
+        tie_method,
+        optimal_scaling: get_value(matches, "optimal_scaling").unwrap_or_default(),
+        test_dont_know: get_value(matches, "test_dont_know").unwrap_or_default(),
+        missing_data,
+        report_formats,
+        precision: matches
+            .try_get_one::<usize>("precision")
+            .ok()
+            .flatten()
+            .copied()
+            .unwrap_or(vars::REPORT_DECIMAL_PLACES),
+        table_captions: get_key_values(matches, "caption"),
+        table_labels: get_key_values(matches, "label"),
+        compare_paths: ["before", "after"]
+            .iter()
+            .filter_map(|id| matches.try_get_one::<String>(id).ok().flatten().cloned())
+            .collect(),
+        tolerance: matches
+            .try_get_one::<f64>("tolerance")
+            .ok()
+            .flatten()
+            .copied(),
+        simulation: SimulationConfig {
+            respondent_count: get_value(matches, "respondents").unwrap_or_default(),
+            seed: get_value(matches, "seed").unwrap_or(vars::RANDOM_SEED),
+            not_applicable_rate: get_value(matches, "na_rate").unwrap_or_default(),
+            answer_weights: get_value(matches, "answer_weights").unwrap_or_default(),
+            quality_weights: get_value(matches, "quality_weights").unwrap_or_default(),
+            correlations: matches
+                .try_get_many::<(String, f64)>("correlate")
+                .ok()
+                .flatten()
+                .map(|values| values.cloned().collect())
+                .unwrap_or_default(),
+        },
+        effect_sizes: matches
+            .try_get_many::<f64>("rho")
+            .ok()
+            .flatten()
+            .map(|values| values.copied().collect())
+            .unwrap_or_default(),
+        target_power: get_value(matches, "power").unwrap_or_default(),
+        simulation_count: get_value::<u64>(matches, "simulations").unwrap_or_default() as usize,
+    }
+}
+
+/// Value of an argument that only some subcommands define.
+fn get_value<T: Clone + Send + Sync + 'static>(matches: &ArgMatches, id: &str) -> Option<T> {
+    matches.try_get_one::<T>(id).ok().flatten().cloned()
+}
+
+/// Parses comma-separated relative frequencies, one per category.
+fn parse_weights<const N: usize>(value: &str) -> Result<[f64; N], String> {
+    let weights: Vec<f64> = value
+        .split(',')
+        .map(|weight| weight.trim().parse::<f64>().map_err(|err| err.to_string()))
+        .collect::<Result<_, _>>()?;
+
+    weights
+        .try_into()
+        .map_err(|weights: Vec<f64>| format!("expected {} weights, got {}", N, weights.len()))
+}
+
+/// Parses a value strictly between 0 and 1.
+fn parse_probability(value: &str) -> Result<f64, String> {
+    match value.trim().parse::<f64>() {
+        Ok(probability) if probability > 0f64 && probability < 1f64 => Ok(probability),
+        Ok(_) => Err("expected a value between 0 and 1".to_owned()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Parses a correlation strictly between -1 and 1.
+fn parse_rho(value: &str) -> Result<f64, String> {
+    match value.trim().parse::<f64>() {
+        Ok(rho) if rho > -1f64 && rho < 1f64 => Ok(rho),
+        Ok(_) => Err("expected a value between -1 and 1".to_owned()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Parses `QUESTION=RHO`.
+fn parse_correlation(value: &str) -> Result<(String, f64), String> {
+    let (question, rho) = value
+        .split_once('=')
+        .ok_or_else(|| "expected QUESTION=RHO".to_owned())?;
+    let rho: f64 = rho
+        .trim()
+        .parse()
+        .map_err(|err: std::num::ParseFloatError| err.to_string())?;
+    Ok((question.trim().to_owned(), rho))
+}
+
+/// Collects repeated `KEY=VALUE` arguments; values without a key are ignored.
+fn get_key_values(matches: &ArgMatches, id: &str) -> HashMap<String, String> {
+    matches
+        .try_get_many::<String>(id)
+        .ok()
+        .flatten()
+        .map(|values| {
+            values
+                .filter_map(|value| value.split_once('='))
+                .map(|(key, value)| (key.to_owned(), value.to_owned()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parses `WAVE=FILE`; without a label the file stem is used as the wave. A value that names
+/// an existing file, or whose part before `=` is not a plain label, is a path containing `=`.
+fn get_input_file(value: &str) -> InputFile {
+    let labelled = value.split_once('=').filter(|(wave, _)| {
+        !Path::new(value).exists() && !wave.is_empty() && !wave.chars().any(std::path::is_separator)
+    });
+
+    match labelled {
+        Some((wave, path)) => InputFile {
+            wave: wave.to_owned(),
+            path: path.to_owned(),
+        },
+        None => InputFile {
+            wave: Path::new(value)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| value.to_owned()),
+            path: value.to_owned(),
+        },
+    }
+}
+
+impl Args {
+    /// Wave labels in the order the inputs were given, without duplicates.
+    pub fn get_waves(&self) -> Vec<String> {
+        let mut waves: Vec<String> = Vec::new();
+        for input in self.inputs.iter() {
+            if !waves.contains(&input.wave) {
+                waves.push(input.wave.to_owned());
+            }
+        }
+        waves
+    }
+}
@@ -1,9 +1,22 @@
-use serde::{Deserialize, Serialize};
+use crate::util::vars;
+use serde::{Deserialize, Serialize, Serializer, ser::SerializeStruct};
+use std::collections::HashMap;
+use struct_iterable::Iterable;
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Iterable)]
 pub struct Feedback {
     pub id: Option<usize>,
 
+    #[serde(rename = "Timestamp", default)]
+    pub timestamp: Option<String>,
+
+    /// Values of the grouping columns declared for the run, keyed by column header.
+    #[serde(skip)]
+    pub groups: HashMap<String, String>,
+
+    #[serde(skip)]
+    pub origin: FeedbackOrigin,
+
     #[serde(rename = "Were requirements documents written and maintained?")]
     pub were_requirements_documents_written: String,
 
@@ -200,200 +213,208 @@ pub struct Feedback {
     pub project_quality_estimate: i8,
 }
 
-#[derive(Debug, Clone)]
-pub struct FeedbackWithData {
-    pub feedback: Feedback,
-
-    pub were_requirements_documents_written_score: i8,
-    pub were_requirements_documents_written_rank: f64,
-
-    pub was_initial_project_plan_written_score: i8,
-    pub was_initial_project_plan_written_rank: f64,
+/// Where a response was read from: its wave label, input file and 1-based data row.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FeedbackOrigin {
+    pub wave: String,
+    pub file: String,
+    pub row: usize,
+}
 
-    pub were_specification_documents_written_score: i8,
-    pub were_specification_documents_written_rank: f64,
+#[derive(Debug, Clone)]
+pub struct InputFile {
+    pub wave: String,
+    pub path: String,
+}
 
-    pub were_technical_design_documents_written_score: i8,
-    pub were_technical_design_documents_written_rank: f64,
+impl Feedback {
+    /// Returns the raw answer text of a question field.
+    pub fn get_answer(&self, field_name: &str) -> Option<&str> {
+        self.iter()
+            .find(|(name, _)| *name == field_name)
+            .and_then(|(_, value)| value.downcast_ref::<String>())
+            .map(String::as_str)
+    }
+}
 
-    pub was_sbom_document_written_score: i8,
-    pub was_sbom_document_written_rank: f64,
+/// Scored practice answers in columns: one score and rank vector per question, in survey
+/// order, each indexed by respondent in input order.
+#[derive(Debug, Clone)]
+pub struct ScoreColumns {
+    pub ids: Vec<Option<usize>>,
+    pub qualities: Vec<i8>,
+    pub questions: Vec<QuestionScores>,
+}
 
-    pub were_draft_design_documents_written_score: i8,
-    pub were_draft_design_documents_written_rank: f64,
+#[derive(Debug, Clone)]
+pub struct QuestionScores {
+    pub question: &'static str,
+    pub scores: Vec<i8>,
+    /// Average rank of each applicable score among the applicable scores, highest first;
+    /// `None` for answers scored as not applicable.
+    pub ranks: Vec<Option<f64>>,
+    /// Sizes of the tie groups of the applicable scores, for the tie correction of rho.
+    pub tie_sizes: Vec<usize>,
+}
 
-    pub were_project_documents_reviewed_score: i8,
-    pub were_project_documents_reviewed_rank: f64,
+impl QuestionScores {
+    /// The ranks of the applicable scores, in respondent order, with their tie groups.
+    pub fn get_applicable_ranking(&self) -> Ranking {
+        Ranking {
+            ranks: self.ranks.iter().flatten().copied().collect(),
+            tie_sizes: self.tie_sizes.to_owned(),
+        }
+    }
+}
 
-    pub were_mutability_and_side_effects_avoided_score: i8,
-    pub were_mutability_and_side_effects_avoided_rank: f64,
+impl ScoreColumns {
+    pub fn get_respondent_count(&self) -> usize {
+        self.qualities.len()
+    }
 
-    pub were_mutable_names_encoded_score: i8,
-    pub were_mutable_names_encoded_rank: f64,
+    /// Returns the applicable scores of a question with the quality estimates of the same
+    /// respondents.
+    pub fn get_applicable_scores(&self, column: &QuestionScores) -> (Vec<f64>, Vec<f64>) {
+        column
+            .scores
+            .iter()
+            .zip(self.qualities.iter())
+            .filter(|(score, _)| **score != vars::WEIGHTING_NOT_APPLICABLE)
+            .map(|(score, quality)| (*score as f64, *quality as f64))
+            .unzip()
+    }
 
-    pub were_nulls_avoided_score: i8,
-    pub were_nulls_avoided_rank: f64,
+    /// Returns the scores of every question for one respondent, in question order.
+    pub fn get_respondent_scores(&self, respondent: usize) -> Vec<i8> {
+        self.questions
+            .iter()
+            .map(|column| column.scores[respondent])
+            .collect()
+    }
+}
 
-    pub was_non_english_used_score: i8,
-    pub was_non_english_used_rank: f64,
+#[derive(Debug, Default, Serialize)]
+pub struct QuestionFrequencyData {
+    pub question: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
+    pub respondent_count: usize,
+    pub answers: Vec<AnswerFrequencyData>,
+    pub scores: Vec<ScoreFrequencyData>,
+    pub crosstab: Vec<CrosstabCellData>,
+}
 
-    pub was_code_style_enforced_score: i8,
-    pub was_code_style_enforced_rank: f64,
+#[derive(Debug, Serialize)]
+pub struct AnswerFrequencyData {
+    pub answer: String,
+    pub score: i8,
+    pub count: usize,
+    pub percentage: f64,
+}
 
-    pub was_code_refactored_score: i8,
-    pub was_code_refactored_rank: f64,
+#[derive(Debug, Serialize)]
+pub struct ScoreFrequencyData {
+    pub score: i8,
+    pub count: usize,
+    pub percentage: f64,
+}
 
-    pub were_posix_timestamps_used_score: i8,
-    pub were_posix_timestamps_used_rank: f64,
-
-    pub were_magic_numbers_replaced_score: i8,
-    pub were_magic_numbers_replaced_rank: f64,
-
-    pub were_critical_code_commented_score: i8,
-    pub were_critical_code_commented_rank: f64,
-
-    pub were_regex_patterns_commented_score: i8,
-    pub were_regex_patterns_commented_rank: f64,
-
-    pub were_todo_comments_avoided_score: i8,
-    pub were_todo_comments_avoided_rank: f64,
-
-    pub was_ai_generated_code_reviewed_score: i8,
-    pub was_ai_generated_code_reviewed_rank: f64,
-
-    pub was_branching_strategy_established_score: i8,
-    pub was_branching_strategy_established_rank: f64,
-
-    pub was_branch_naming_strategy_established_score: i8,
-    pub was_branch_naming_strategy_established_rank: f64,
-
-    pub was_commit_message_format_established_score: i8,
-    pub was_commit_message_format_established_rank: f64,
-
-    pub was_merging_strategy_established_score: i8,
-    pub was_merging_strategy_established_rank: f64,
-
-    pub were_higher_level_design_issues_considered_score: i8,
-    pub were_higher_level_design_issues_considered_rank: f64,
-
-    pub was_reviewer_required_score: i8,
-    pub was_reviewer_required_rank: f64,
-
-    pub were_formatter_and_linter_tools_established_score: i8,
-    pub were_formatter_and_linter_tools_established_rank: f64,
-
-    pub were_static_analysis_tools_established_score: i8,
-    pub were_static_analysis_tools_established_rank: f64,
-
-    pub were_semi_automated_tools_configured_score: i8,
-    pub were_semi_automated_tools_configured_rank: f64,
-
-    pub were_semi_automated_processes_run_score: i8,
-    pub were_semi_automated_processes_run_rank: f64,
-
-    pub were_automated_tests_run_score: i8,
-    pub were_automated_tests_run_rank: f64,
-
-    pub were_tests_written_like_production_code_score: i8,
-    pub were_tests_written_like_production_code_rank: f64,
-
-    pub were_unit_tests_written_with_boc_score: i8,
-    pub were_unit_tests_written_with_boc_rank: f64,
-
-    pub project_quality_estimate_rank: f64,
-}
-
-impl FeedbackWithData {
-    pub fn new(feedback: Feedback) -> Self {
-        FeedbackWithData {
-            feedback,
-            were_requirements_documents_written_score: 0,
-            were_requirements_documents_written_rank: 0f64,
-            was_initial_project_plan_written_score: 0,
-            was_initial_project_plan_written_rank: 0f64,
-            were_specification_documents_written_score: 0,
-            were_specification_documents_written_rank: 0f64,
-            were_technical_design_documents_written_score: 0,
-            were_technical_design_documents_written_rank: 0f64,
-            was_sbom_document_written_score: 0,
-            was_sbom_document_written_rank: 0f64,
-            were_draft_design_documents_written_score: 0,
-            were_draft_design_documents_written_rank: 0f64,
-            were_project_documents_reviewed_score: 0,
-            were_project_documents_reviewed_rank: 0f64,
-            were_mutability_and_side_effects_avoided_score: 0,
-            were_mutability_and_side_effects_avoided_rank: 0f64,
-            were_mutable_names_encoded_score: 0,
-            were_mutable_names_encoded_rank: 0f64,
-            were_nulls_avoided_score: 0,
-            were_nulls_avoided_rank: 0f64,
-            was_non_english_used_score: 0,
-            was_non_english_used_rank: 0f64,
-            was_code_style_enforced_score: 0,
-            was_code_style_enforced_rank: 0f64,
-            was_code_refactored_score: 0,
-            was_code_refactored_rank: 0f64,
-            were_posix_timestamps_used_score: 0,
-            were_posix_timestamps_used_rank: 0f64,
-            were_magic_numbers_replaced_score: 0,
-            were_magic_numbers_replaced_rank: 0f64,
-            were_critical_code_commented_score: 0,
-            were_critical_code_commented_rank: 0f64,
-            were_regex_patterns_commented_score: 0,
-            were_regex_patterns_commented_rank: 0f64,
-            were_todo_comments_avoided_score: 0,
-            were_todo_comments_avoided_rank: 0f64,
-            was_ai_generated_code_reviewed_score: 0,
-            was_ai_generated_code_reviewed_rank: 0f64,
-            was_branching_strategy_established_score: 0,
-            was_branching_strategy_established_rank: 0f64,
-            was_branch_naming_strategy_established_score: 0,
-            was_branch_naming_strategy_established_rank: 0f64,
-            was_commit_message_format_established_score: 0,
-            was_commit_message_format_established_rank: 0f64,
-            was_merging_strategy_established_score: 0,
-            was_merging_strategy_established_rank: 0f64,
-            were_higher_level_design_issues_considered_score: 0,
-            were_higher_level_design_issues_considered_rank: 0f64,
-            was_reviewer_required_score: 0,
-            was_reviewer_required_rank: 0f64,
-            were_formatter_and_linter_tools_established_score: 0,
-            were_formatter_and_linter_tools_established_rank: 0f64,
-            were_static_analysis_tools_established_score: 0,
-            were_static_analysis_tools_established_rank: 0f64,
-            were_semi_automated_tools_configured_score: 0,
-            were_semi_automated_tools_configured_rank: 0f64,
-            were_semi_automated_processes_run_score: 0,
-            were_semi_automated_processes_run_rank: 0f64,
-            were_automated_tests_run_score: 0,
-            were_automated_tests_run_rank: 0f64,
-            were_tests_written_like_production_code_score: 0,
-            were_tests_written_like_production_code_rank: 0f64,
-            were_unit_tests_written_with_boc_score: 0,
-            were_unit_tests_written_with_boc_rank: 0f64,
-            project_quality_estimate_rank: 0f64,
-        }
-    }
+/// Respondents with a given practice score and quality estimate; the percentage is
+/// of the respondents with that score.
+#[derive(Debug, Serialize)]
+pub struct CrosstabCellData {
+    pub score: i8,
+    pub quality: i8,
+    pub count: usize,
+    pub percentage: f64,
 }
 
+/// One respondent's answer to one practice question, in long format.
 #[derive(Debug, Serialize)]
+pub struct RespondentScoreData {
+    pub respondent_id: Option<usize>,
+    pub wave: String,
+    pub question: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
+    pub answer: String,
+    pub score: i8,
+    /// Rank of the score among the applicable answers; `None` when not applicable.
+    pub rank: Option<f64>,
+    pub quality: i8,
+    /// Rank of the quality estimate among the respondents with an applicable answer.
+    pub quality_rank: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct QuestionCorrelationData {
     pub question: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
     pub rho: f64,
+    pub rho_ci_lower: f64,
+    pub rho_ci_upper: f64,
     pub p_value: f64,
+    /// Benjamini-Hochberg adjusted across the questions or factors reported together.
+    pub p_value_adjusted: f64,
+    /// Respondents with an applicable answer to the question.
     pub answer_count: usize,
+    /// Respondents of the pooled correlation, including those with imputed answers; `None`
+    /// without imputation.
+    pub imputed_answer_count: Option<usize>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct QuestionImpactData {
     pub question: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
     pub impact_average: f32,
     pub answer_count: usize,
 }
 
 #[derive(Debug, Serialize)]
+pub struct ImpactAverageData {
+    pub impact_average: f64,
+}
+
+/// Why a practice question has no correlation or no diff.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// Fewer than three applicable answers.
+    #[default]
+    TooFewAnswers,
+    /// Every applicable answer has the same score or quality estimate.
+    NoVariation,
+    /// No impact ratings to compare the correlation with.
+    NoImpactRating,
+}
+
+impl SkipReason {
+    pub fn get_description(&self) -> &str {
+        match self {
+            SkipReason::TooFewAnswers => "fewer than three applicable answers",
+            SkipReason::NoVariation => "no variation in the applicable answers",
+            SkipReason::NoImpactRating => "no impact ratings",
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SkippedQuestionData {
+    pub question: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
+    pub answer_count: usize,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct QuestionDataDiffs {
     pub question_correlation: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
     pub rho: f64,
     pub p_value: f64,
     pub correlation_answer_count: usize,
@@ -401,4 +422,701 @@ pub struct QuestionDataDiffs {
     pub impact_average: f32,
     pub impact_answer_count: usize,
     pub diff: f64,
+    pub quadrant: Quadrant,
+}
+
+/// Survey metadata of a question, flattened into the rows that report on it.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct QuestionInfo {
+    pub question_id: String,
+    pub question_text: String,
+    pub question_label: String,
+    pub question_category: String,
+}
+
+/// Writes the fields of a missing `QuestionInfo` as nulls, so that every row of a result has
+/// the same fields, e.g. the mean practice score next to the practice questions.
+fn serialize_question_info<S: Serializer>(
+    info: &Option<QuestionInfo>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match info {
+        Some(info) => info.serialize(serializer),
+        None => {
+            let mut state = serializer.serialize_struct("QuestionInfo", 4)?;
+            for field in [
+                "question_id",
+                "question_text",
+                "question_label",
+                "question_category",
+            ] {
+                state.serialize_field(field, &None::<String>)?;
+            }
+            state.end()
+        }
+    }
+}
+
+/// Position of a question when the correlation with quality is split at the median rho
+/// and the perceived impact at the overall impact average.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Quadrant {
+    EffectiveAndValued,
+    Underrated,
+    Overrated,
+    #[default]
+    LowPriority,
+}
+
+impl Quadrant {
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Quadrant::EffectiveAndValued => "Effective and valued",
+            Quadrant::Underrated => "Underrated",
+            Quadrant::Overrated => "Overrated",
+            Quadrant::LowPriority => "Low priority",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImpactDistributionData {
+    pub question: String,
+    pub ratings: Vec<f32>,
+    pub counts: Vec<usize>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct QuestionOrdinalRegressionData {
+    pub question: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
+    pub answer_count: usize,
+    pub coefficient: f64,
+    pub standard_error: f64,
+    pub odds_ratio: f64,
+    pub odds_ratio_ci_lower: f64,
+    pub odds_ratio_ci_upper: f64,
+    pub p_value: f64,
+    pub thresholds: Vec<OrdinalThresholdData>,
+    pub log_likelihood: f64,
+    pub null_log_likelihood: f64,
+    pub likelihood_ratio_chi_squared: f64,
+    pub likelihood_ratio_p_value: f64,
+    pub proportional_odds_chi_squared: Option<f64>,
+    pub proportional_odds_df: usize,
+    pub proportional_odds_p_value: Option<f64>,
+    pub converged: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrdinalThresholdData {
+    pub boundary: String,
+    pub estimate: f64,
+    pub standard_error: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FactorAnalysisData {
+    pub questions: Vec<String>,
+    pub respondent_count: usize,
+    pub scree: Vec<ScreeData>,
+    pub solutions: Vec<FactorSolutionData>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScreeData {
+    pub component: usize,
+    pub eigenvalue: f64,
+    pub explained_variance: f64,
+    pub cumulative_explained_variance: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FactorSolutionData {
+    pub method: String,
+    pub factor_count: usize,
+    pub loadings: Vec<FactorLoadingData>,
+    pub explained_variance: Vec<f64>,
+    pub factor_correlations: Option<Vec<Vec<f64>>>,
+    pub scores: Vec<FactorScoreData>,
+    pub quality_correlations: Vec<QuestionCorrelationData>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FactorLoadingData {
+    pub question: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
+    pub loadings: Vec<f64>,
+    pub communality: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FactorScoreData {
+    pub id: Option<usize>,
+    pub scores: Vec<f64>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ClusterAnalysisData {
+    pub method: String,
+    pub seed: u64,
+    pub best_cluster_count: usize,
+    pub solutions: Vec<ClusterSolutionData>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClusterSolutionData {
+    pub cluster_count: usize,
+    pub silhouette: f64,
+    pub clusters: Vec<ClusterData>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClusterData {
+    pub cluster: usize,
+    pub size: usize,
+    pub medoid_id: Option<usize>,
+    pub centroid: Vec<QuestionScoreData>,
+    pub respondent_ids: Vec<Option<usize>>,
+    pub quality_average: f64,
+    pub quality_distribution: Vec<QualityCountData>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuestionScoreData {
+    pub question: String,
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QualityCountData {
+    pub quality: i8,
+    pub count: usize,
+    pub proportion: f64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SubgroupAnalysisData {
+    pub column: String,
+    pub groups: Vec<SubgroupData>,
+    pub correlation_tests: Vec<SubgroupCorrelationTestData>,
+    pub impact_tests: Vec<SubgroupImpactTestData>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubgroupData {
+    pub group: String,
+    pub respondent_count: usize,
+    pub correlations: Vec<QuestionCorrelationData>,
+    pub impacts: Vec<QuestionImpactData>,
+    pub impact_average: f64,
+    pub diffs: Vec<QuestionDataDiffs>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubgroupCorrelationTestData {
+    pub question: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
+    pub group_count: usize,
+    pub chi_squared: f64,
+    pub df: usize,
+    pub p_value: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubgroupImpactTestData {
+    pub question: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
+    pub group_count: usize,
+    pub h_statistic: f64,
+    pub df: usize,
+    pub p_value: f64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct WaveComparisonData {
+    pub wave_before: String,
+    pub wave_after: String,
+    pub respondent_count_before: usize,
+    pub respondent_count_after: usize,
+    pub questions: Vec<QuestionWaveComparisonData>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuestionWaveComparisonData {
+    pub question: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
+    pub question_impact: String,
+    pub rho_before: Option<f64>,
+    pub rho_after: Option<f64>,
+    pub rho_change: Option<f64>,
+    pub rho_z: Option<f64>,
+    pub rho_p_value: Option<f64>,
+    pub impact_average_before: Option<f32>,
+    pub impact_average_after: Option<f32>,
+    pub impact_average_change: Option<f32>,
+    pub impact_mann_whitney_u: Option<f64>,
+    pub impact_z: Option<f64>,
+    pub impact_p_value: Option<f64>,
+    pub adoption_rate_before: Option<f64>,
+    pub adoption_rate_after: Option<f64>,
+    pub adoption_rate_change: Option<f64>,
+    pub adoption_z: Option<f64>,
+    pub adoption_p_value: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResultsData {
+    pub metadata: RunMetadata,
+    pub correlations: Vec<QuestionCorrelationData>,
+    pub impacts: Vec<QuestionImpactData>,
+    pub impact_average: f64,
+    pub diffs: Vec<QuestionDataDiffs>,
+    pub frequencies: Vec<QuestionFrequencyData>,
+    pub ordinal_regressions: Vec<QuestionOrdinalRegressionData>,
+    pub factor_analysis: FactorAnalysisData,
+    pub clusters: Vec<ClusterAnalysisData>,
+    pub influences: Vec<QuestionInfluenceData>,
+    pub missing_data: Vec<QuestionMissingData>,
+    pub optimal_scalings: Option<Vec<QuestionOptimalScalingData>>,
+    pub subgroups: Option<Vec<SubgroupAnalysisData>>,
+    pub skipped_questions: Vec<SkippedQuestionData>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunMetadata {
+    pub tool: String,
+    pub version: String,
+    /// POSIX timestamp of the run in seconds.
+    pub run_timestamp: u64,
+    pub row_count: usize,
+    pub inputs: Vec<InputMetadata>,
+    pub parameters: RunParameters,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InputMetadata {
+    pub wave: String,
+    pub path: String,
+    pub sha256: String,
+    pub row_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunParameters {
+    pub weighting_full: i8,
+    pub weighting_partial: i8,
+    pub weighting_partial_negative: i8,
+    pub weighting_full_negative: i8,
+    pub weighting_not_applicable: i8,
+    pub comparable_rho_multiplier: f64,
+    pub round_decimal_places: u32,
+    pub significance_level: f64,
+    pub correlation_method: String,
+    pub p_value_adjustment: String,
+    pub ordinal_regression_method: String,
+    pub factor_methods: Vec<String>,
+    pub factor_eigenvalue_threshold: f64,
+    pub cluster_methods: Vec<String>,
+    pub max_cluster_count: usize,
+    pub cluster_restarts: usize,
+    pub random_seed: u64,
+    pub group_columns: Vec<String>,
+    pub missing_data: MissingDataStrategy,
+    pub optimal_scaling: bool,
+    pub test_dont_know: bool,
+    pub tie_method: TieMethod,
+}
+
+/// The result sections that `compare` reads from a results directory or `results.json`.
+#[derive(Debug, Deserialize)]
+pub struct ComparableResultsData {
+    pub correlations: Vec<QuestionCorrelationData>,
+    pub impacts: Vec<QuestionImpactData>,
+    pub diffs: Vec<QuestionDataDiffs>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ResultComparisonData {
+    pub before: String,
+    pub after: String,
+    pub tolerance: Option<f64>,
+    pub changed_question_count: usize,
+    pub questions: Vec<QuestionResultComparisonData>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuestionResultComparisonData {
+    pub question: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
+    pub rho_before: Option<f64>,
+    pub rho_after: Option<f64>,
+    pub rho_change: Option<f64>,
+    pub p_value_before: Option<f64>,
+    pub p_value_after: Option<f64>,
+    pub p_value_change: Option<f64>,
+    pub answer_count_before: Option<usize>,
+    pub answer_count_after: Option<usize>,
+    pub answer_count_change: Option<i64>,
+    pub impact_average_before: Option<f32>,
+    pub impact_average_after: Option<f32>,
+    pub impact_average_change: Option<f32>,
+    /// Position when the questions are sorted by diff, largest first.
+    pub diff_rank_before: Option<usize>,
+    pub diff_rank_after: Option<usize>,
+    pub diff_rank_change: Option<i64>,
+    pub quadrant_before: Option<Quadrant>,
+    pub quadrant_after: Option<Quadrant>,
+    pub is_significance_changed: bool,
+    pub is_quadrant_changed: bool,
+    /// Whether any change exceeds the tolerance or a significance or quadrant changed.
+    pub is_changed: bool,
+}
+
+/// Why a practice answer has no score. All reasons score as not applicable.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingReason {
+    /// The respondent did not know, e.g. how team members used generative AI tools.
+    DontKnow,
+    NotApplicable,
+    /// There was nothing to apply the practice to, e.g. no Regex patterns were written.
+    NotUsed,
+}
+
+/// Which respondents each correlation with the quality estimate uses.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum MissingDataStrategy {
+    /// Every respondent with an applicable answer to the question, so the respondents differ
+    /// between questions.
+    Pairwise,
+    /// Only the respondents with applicable answers to all of `questions`, by ID or field name.
+    Listwise { questions: Vec<String> },
+    /// Every respondent, with the unscored answers imputed by chained equations and rho pooled
+    /// over the imputations by Rubin's rules.
+    MultipleImputation {
+        imputation_count: usize,
+        iteration_count: usize,
+    },
+}
+
+impl MissingDataStrategy {
+    pub fn get_description(&self) -> String {
+        match self {
+            MissingDataStrategy::Pairwise => "pairwise deletion".to_owned(),
+            MissingDataStrategy::Listwise { questions } => {
+                format!("listwise deletion across {}", questions.join(", "))
+            }
+            MissingDataStrategy::MultipleImputation {
+                imputation_count, ..
+            } => format!("multiple imputation ({} imputations)", imputation_count),
+        }
+    }
+}
+
+/// Answers of a practice question without a score, counted by reason.
+#[derive(Debug, Default, Serialize)]
+pub struct QuestionMissingData {
+    pub question: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
+    pub respondent_count: usize,
+    pub answer_count: usize,
+    pub dont_know_count: usize,
+    pub not_applicable_count: usize,
+    pub not_used_count: usize,
+    /// Only when requested and both groups have answers.
+    pub dont_know_quality_test: Option<DontKnowQualityTestData>,
+}
+
+/// Mann-Whitney U test of the quality estimates of respondents who did not know against those
+/// with a scored answer; a positive z means those who did not know estimate higher quality.
+#[derive(Debug, Serialize)]
+pub struct DontKnowQualityTestData {
+    pub dont_know_quality_mean: f64,
+    pub answered_quality_mean: f64,
+    pub mann_whitney_u: f64,
+    pub z: f64,
+    pub p_value: f64,
+}
+
+/// Category values of a practice question estimated from the data, next to the fixed answer
+/// weightings. The correlations are Pearson's r with the quality estimate, which the
+/// estimated values maximise.
+#[derive(Debug, Default, Serialize)]
+pub struct QuestionOptimalScalingData {
+    pub question: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
+    pub answer_count: usize,
+    pub categories: Vec<CategoryScalingData>,
+    pub fixed_r: f64,
+    /// `None` when the monotone fit pools every category into one value.
+    pub optimal_r: Option<f64>,
+    /// Out-of-fold r of values estimated without the respondents they are applied to.
+    pub cross_validated_r: Option<f64>,
+    /// Whether the cross-validated values are more strongly associated than the fixed ones.
+    pub is_improvement: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryScalingData {
+    /// The fixed weighting of the answers in this category.
+    pub weighting: i8,
+    pub answer_count: usize,
+    pub quality_mean: f64,
+    /// Estimated value on the scale of the fixed weightings, from the lowest to the highest.
+    pub value: Option<f64>,
+}
+
+/// Scores that replace the answer weightings of `get_answer_score` when rescoring practices.
+#[derive(Debug, Clone, Copy)]
+pub struct WeightingScheme {
+    pub name: &'static str,
+    pub full: i8,
+    pub partial: i8,
+    pub partial_neg: i8,
+    pub full_neg: i8,
+}
+
+impl WeightingScheme {
+    /// Rescores a weighting; not applicable answers stay not applicable.
+    pub fn get_score(&self, weighting: i8) -> i8 {
+        if weighting == vars::WEIGHTING_FULL {
+            self.full
+        } else if weighting == vars::WEIGHTING_PARTIAL {
+            self.partial
+        } else if weighting == vars::WEIGHTING_PARTIAL_NEG {
+            self.partial_neg
+        } else if weighting == vars::WEIGHTING_FULL_NEG {
+            self.full_neg
+        } else {
+            vars::WEIGHTING_NOT_APPLICABLE
+        }
+    }
+}
+
+/// Correlations rerun under alternative answer weighting schemes, compared with the standard
+/// weightings.
+#[derive(Debug, Serialize)]
+pub struct WeightingSensitivityData {
+    pub schemes: Vec<WeightingSchemeSummaryData>,
+    pub questions: Vec<QuestionWeightingSensitivityData>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WeightingSchemeSummaryData {
+    pub scheme: String,
+    pub full: i8,
+    pub partial: i8,
+    pub partial_neg: i8,
+    pub full_neg: i8,
+    pub significant_count: usize,
+    pub significance_changed_count: usize,
+    pub mean_absolute_rho_change: f64,
+    pub max_absolute_rho_change: f64,
+    pub mean_absolute_r_change: f64,
+    pub max_absolute_r_change: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuestionWeightingSensitivityData {
+    pub question: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
+    pub rho: f64,
+    pub p_value: f64,
+    pub rho_min: f64,
+    pub rho_max: f64,
+    /// Pearson's r of the standard scores with the quality estimate.
+    pub r: f64,
+    pub r_min: f64,
+    pub r_max: f64,
+    /// Whether every scheme gives a correlation with the same significance as the standard
+    /// weightings.
+    pub is_robust: bool,
+    pub schemes: Vec<SchemeCorrelationData>,
+}
+
+/// The correlation of a question under one scheme; `None` where the rescored answers have
+/// no correlation, e.g. when a scheme leaves too few applicable answers.
+#[derive(Debug, Serialize)]
+pub struct SchemeCorrelationData {
+    pub scheme: String,
+    pub rho: Option<f64>,
+    pub p_value: Option<f64>,
+    pub p_value_adjusted: Option<f64>,
+    pub answer_count: Option<usize>,
+    pub rho_change: Option<f64>,
+    pub r: Option<f64>,
+    pub r_change: Option<f64>,
+    pub is_significance_changed: bool,
+}
+
+/// Leave-one-out recomputation of a correlation without each respondent with an applicable
+/// answer in turn.
+#[derive(Debug, Default, Serialize)]
+pub struct QuestionInfluenceData {
+    pub question: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
+    pub rho: f64,
+    pub p_value: f64,
+    pub answer_count: usize,
+    pub rho_min: f64,
+    pub rho_max: f64,
+    /// Respondents whose removal alone moves the p-value across the significance level.
+    pub significance_changing_count: usize,
+    pub is_significance_changed: bool,
+    /// Respondents whose removal changes rho the most, most influential first.
+    pub influential_respondents: Vec<RespondentInfluenceData>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RespondentInfluenceData {
+    pub respondent_id: Option<usize>,
+    /// Rho and p-value without this respondent.
+    pub rho: f64,
+    pub p_value: f64,
+    pub rho_change: f64,
+    pub is_significance_changed: bool,
+}
+
+/// Sample sizes needed to detect correlations with the one-sided test at the significance
+/// level, for planning the next survey wave.
+#[derive(Debug, Serialize)]
+pub struct PowerAnalysisData {
+    pub significance_level: f64,
+    pub target_power: f64,
+    pub simulation_count: usize,
+    pub effect_sizes: Vec<EffectSizePowerData>,
+    pub questions: Vec<QuestionPowerData>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EffectSizePowerData {
+    pub rho: f64,
+    pub required_answer_count_pearson: Option<usize>,
+    pub required_answer_count_spearman: Option<usize>,
+    /// Questions whose applicable answers already reach the Spearman sample size.
+    pub powered_question_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuestionPowerData {
+    pub question: String,
+    #[serde(flatten, serialize_with = "serialize_question_info")]
+    pub question_info: Option<QuestionInfo>,
+    pub rho: f64,
+    pub answer_count: usize,
+    pub respondent_count: usize,
+    /// Analytic Spearman power of the current applicable answers for the observed rho.
+    pub power: f64,
+    pub required_answer_count_pearson: Option<usize>,
+    pub required_answer_count_spearman: Option<usize>,
+    /// Smallest answer count whose simulated power reaches the target, found by bisection
+    /// rather than read off the power curve; `None` beyond `MAX_SIMULATED_ANSWER_COUNT`.
+    pub required_answer_count_simulated: Option<usize>,
+    /// Respondents needed for the Spearman answer count at the current share of applicable
+    /// answers.
+    pub required_respondent_count: Option<usize>,
+    pub power_curve: Vec<PowerCurvePointData>,
+}
+
+/// Power to detect the observed rho with a given number of applicable answers. The simulated
+/// power resamples the observed score and quality pairs, ties included.
+#[derive(Debug, Serialize)]
+pub struct PowerCurvePointData {
+    pub answer_count: usize,
+    pub power_pearson: f64,
+    pub power_spearman: f64,
+    pub power_simulated: f64,
+}
+
+/// How tied values share ranks, e.g. for the values 3, 2, 2, 1.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TieMethod {
+    /// The mean of the tied positions: 1, 2.5, 2.5, 4.
+    Average,
+    /// The lowest tied position: 1, 2, 2, 4.
+    Min,
+    /// The highest tied position: 1, 3, 3, 4.
+    Max,
+    /// Consecutive ranks per distinct value: 1, 2, 2, 3.
+    Dense,
+    /// Distinct ranks in order of appearance: 1, 2, 3, 4.
+    Ordinal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ranking {
+    /// Rank of each value, in the order of the input.
+    pub ranks: Vec<f64>,
+    /// Size of each group of equal values, highest value first; untied values have size 1.
+    pub tie_sizes: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuestionKind {
+    /// Whether a development practice was followed, scored by its answer weighting.
+    Practice,
+    /// How impactful on quality a practice is thought to be, on the impact rating scale.
+    Impact,
+    /// The estimated project quality that the practices are correlated with.
+    Outcome,
+}
+
+/// A survey question as every analysis stage sees it.
+#[derive(Debug, Clone)]
+pub struct Question {
+    /// Stable across runs: `Pnn` for a practice, `Inn` for its impact rating.
+    pub id: &'static str,
+    pub field_name: &'static str,
+    pub kind: QuestionKind,
+    pub text: &'static str,
+    pub label: &'static str,
+    pub category: &'static str,
+    /// The impact rating of a practice or the practice of an impact rating;
+    /// `None` for the outcome.
+    pub pair: Option<&'static str>,
+    /// Scored answers of a practice, from the lowest to the highest score; empty for impact
+    /// ratings and the outcome.
+    pub answers: &'static [&'static str],
+    /// Answers of a practice scored as not applicable.
+    pub unscored_answers: &'static [&'static str],
+}
+
+/// Parameters of a simulated survey export.
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    pub respondent_count: usize,
+    pub seed: u64,
+    /// Share of practice answers scored as not applicable, e.g. "Not applicable" or
+    /// "I don't know", and of impact ratings left blank.
+    pub not_applicable_rate: f64,
+    /// Relative frequencies of the lowest, middle and highest scored answer of each practice,
+    /// e.g. "No", "Yes, sometimes" and "Yes".
+    pub answer_weights: [f64; 3],
+    /// Relative frequencies of the quality estimates 1 to 5.
+    pub quality_weights: [f64; 5],
+    /// Target Spearman's rho with the quality estimate, keyed by practice ID or field name.
+    /// Other practices are generated independently of quality.
+    pub correlations: Vec<(String, f64)>,
+}
+
+/// Generated survey responses shaped like the form export: a header row and one row of
+/// answers per respondent.
+#[derive(Debug, Clone)]
+pub struct SimulatedSurvey {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
 }
@@ -5,6 +5,26 @@ pub static WEIGHTING_PARTIAL_NEG: i8 = -1;
 pub static WEIGHTING_NOT_APPLICABLE: i8 = 0;
 
 pub static COMPARABLE_RHO_MULTIPLIER: f64 = 5f64;
+pub static IMPACT_RATING_MIN: u8 = 1;
+pub static IMPACT_RATING_MAX: u8 = 5;
+pub static IMPACT_HEADER_PREFIX: &str = "In general, how impactful on quality are the following development practices in your opinion?\n1 means not impactful at all and 5 means very impactful.  ";
+pub static SIGNIFICANCE_LEVEL: f64 = 0.05;
+pub static SIGNIFICANCE_MARKERS: [(f64, &str); 3] = [(0.001, "***"), (0.01, "**"), (0.05, "*")];
+pub static FACTOR_EIGENVALUE_THRESHOLD: f64 = 1f64;
+pub static MAX_CLUSTER_COUNT: usize = 8;
+pub static CLUSTER_RESTARTS: usize = 10;
+pub static RANDOM_SEED: u64 = 42;
+pub static CROSS_VALIDATION_FOLDS: usize = 5;
+pub static INFLUENTIAL_RESPONDENT_COUNT: usize = 3;
+pub static IMPUTATION_COUNT: usize = 20;
+pub static IMPUTATION_ITERATIONS: usize = 10;
+pub static POWER_CURVE_ANSWER_COUNTS: [usize; 8] = [20, 30, 50, 75, 100, 150, 200, 300];
+pub static MAX_SIMULATED_ANSWER_COUNT: usize = 5000;
 pub static ROUND_DECIMAL_PLACES_MULTIPLIER: u64 = u64::pow(10, ROUND_DEMICAL_PLACES);
 
-static ROUND_DEMICAL_PLACES: u32 = 5;
+pub static REPORT_DECIMAL_PLACES: usize = 3;
+
+pub static MAX_SOLVER_ITERATIONS: usize = 100;
+pub static SOLVER_TOLERANCE: f64 = 1e-8;
+
+pub static ROUND_DEMICAL_PLACES: u32 = 5;